@@ -2,9 +2,9 @@ use anyhow::Result;
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool, Row};
 use tokio::fs;
 use tracing::{info, warn};
-use serde_json;
 use std::path::Path;
-use crate::models::{SolarSystem, Constellation, SystemHierarchy, SystemInfo, RegionInfo, ConstellationInfo, GateConnection, SystemConnections, CompleteSystemHierarchy, ConstellationWithSystems, RegionWithConstellations, SecurityInfo, CelestialInfo, NavigationInfo, SystemMetadata, ConstellationMetadata, TypeName, TypeNameResponse};
+use crate::spatial::SpatialIndex;
+use crate::models::{SolarSystem, Constellation, SystemHierarchy, RegionInfo, ConstellationInfo, GateConnection, CompleteSystemHierarchy, SecurityInfo, CelestialInfo, NavigationInfo, SystemMetadata, TypeName, TypeNameResponse};
 
 #[derive(Clone)]
 pub struct Database {
@@ -74,7 +74,7 @@ impl Database {
     }
 
     pub async fn search_type_names(&self, query: &str, limit: usize) -> Result<TypeNameResponse> {
-        let limit = limit.min(100).max(1);
+        let limit = limit.clamp(1, 100);
         
         let rows = sqlx::query(
             "SELECT type_id, name FROM type_names 
@@ -104,26 +104,19 @@ impl Database {
         })
     }
 
-    pub async fn get_all_type_names(&self, limit: usize, offset: usize) -> Result<Vec<TypeName>> {
-        let rows = sqlx::query(
-            "SELECT type_id, name FROM type_names 
-             ORDER BY type_id ASC 
-             LIMIT ? OFFSET ?"
-        )
-        .bind(limit as i32)
-        .bind(offset as i32)
-        .fetch_all(&self.pool)
-        .await?;
+    /// Every type name, for building the in-memory search index
+    pub async fn load_type_names(&self) -> Result<Vec<TypeName>> {
+        let rows = sqlx::query("SELECT type_id, name FROM type_names ORDER BY type_id ASC")
+            .fetch_all(&self.pool)
+            .await?;
 
-        let type_names: Vec<TypeName> = rows
+        Ok(rows
             .into_iter()
             .map(|row| TypeName {
                 type_id: row.get::<i32, _>("type_id") as u32,
                 name: row.get("name"),
             })
-            .collect();
-
-        Ok(type_names)
+            .collect())
     }
 
     #[allow(dead_code)]
@@ -241,6 +234,24 @@ impl Database {
             .collect())
     }
 
+    pub async fn load_all_connections(&self) -> Result<Vec<GateConnection>> {
+        let rows = sqlx::query(
+            "SELECT id, from_system_id, to_system_id, connection_type FROM gate_connections ORDER BY id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| GateConnection {
+                id: row.get("id"),
+                from_system_id: row.get("from_system_id"),
+                to_system_id: row.get("to_system_id"),
+                connection_type: row.get("connection_type"),
+            })
+            .collect())
+    }
+
     pub async fn is_empty(&self) -> Result<bool> {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM systems")
             .fetch_one(&self.pool)
@@ -524,9 +535,9 @@ impl Database {
     }
 
     /// Get complete hierarchical information for a system (system -> constellation -> region)
-    pub async fn get_system_hierarchy(&self, system_id: u32) -> Result<Option<SystemHierarchy>> {
+    pub async fn get_system_hierarchy(&self, index: &SpatialIndex, system_id: u32) -> Result<Option<SystemHierarchy>> {
         let row = sqlx::query(
-            "SELECT s.id, s.name as system_name, s.x, s.y, s.z, s.region_id, s.constellation_id, s.faction_id,
+            "SELECT s.id, s.region_id, s.constellation_id,
                     c.name as constellation_name, c.region_id as constellation_region_id,
                     r.name as region_name
              FROM systems s
@@ -539,18 +550,8 @@ impl Database {
         .await?;
 
         if let Some(row) = row {
-            let system = SystemInfo {
-                id: row.get("id"),
-                name: row.get("system_name"),
-                center: [
-                    row.get::<Option<f64>, _>("x").unwrap_or(0.0),
-                    row.get::<Option<f64>, _>("y").unwrap_or(0.0),
-                    row.get::<Option<f64>, _>("z").unwrap_or(0.0),
-                ],
-                region_id: row.get("region_id"),
-                constellation_id: row.get("constellation_id"),
-                faction_id: row.get("faction_id"),
-                distance: None,
+            let Some(system) = index.system_info(row.get("id"), None) else {
+                return Ok(None);
             };
 
             let constellation = if let (Some(constellation_id), Some(constellation_name)) = 
@@ -585,34 +586,11 @@ impl Database {
     }
 
     /// Get complete hierarchical information with all related systems and constellations
-    pub async fn get_complete_system_hierarchy(&self, system_id: u32) -> Result<Option<CompleteSystemHierarchy>> {
+    pub async fn get_complete_system_hierarchy(&self, index: &SpatialIndex, system_id: u32) -> Result<Option<CompleteSystemHierarchy>> {
         use crate::models::*;
 
         // First get the target system
-        let target_system_row = sqlx::query(
-            "SELECT s.id, s.name as system_name, s.x, s.y, s.z, s.region_id, s.constellation_id, s.faction_id
-             FROM systems s
-             WHERE s.id = ?"
-        )
-        .bind(system_id)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        let target_system = if let Some(row) = target_system_row {
-            SystemInfo {
-                id: row.get("id"),
-                name: row.get("system_name"),
-                center: [
-                    row.get::<Option<f64>, _>("x").unwrap_or(0.0),
-                    row.get::<Option<f64>, _>("y").unwrap_or(0.0),
-                    row.get::<Option<f64>, _>("z").unwrap_or(0.0),
-                ],
-                region_id: row.get("region_id"),
-                constellation_id: row.get("constellation_id"),
-                faction_id: row.get("faction_id"),
-                distance: None,
-            }
-        } else {
+        let Some(target_system) = index.system_info(system_id, None) else {
             return Ok(None);
         };
 
@@ -628,28 +606,14 @@ impl Database {
             if let Some(constellation_row) = constellation_row {
                 // Get all systems in this constellation
                 let systems_in_constellation = sqlx::query(
-                    "SELECT id, name, x, y, z, region_id, constellation_id, faction_id
-                     FROM systems 
-                     WHERE constellation_id = ?"
+                    "SELECT id FROM systems WHERE constellation_id = ?"
                 )
                 .bind(constellation_id)
                 .fetch_all(&self.pool)
                 .await?;
 
                 let systems = systems_in_constellation.into_iter()
-                    .map(|row| SystemInfo {
-                        id: row.get("id"),
-                        name: row.get("name"),
-                        center: [
-                            row.get::<Option<f64>, _>("x").unwrap_or(0.0),
-                            row.get::<Option<f64>, _>("y").unwrap_or(0.0),
-                            row.get::<Option<f64>, _>("z").unwrap_or(0.0),
-                        ],
-                        region_id: row.get("region_id"),
-                        constellation_id: row.get("constellation_id"),
-                        faction_id: row.get("faction_id"),
-                        distance: None,
-                    })
+                    .filter_map(|row| index.system_info(row.get("id"), None))
                     .collect();
 
                 Some(ConstellationWithSystems {
@@ -689,28 +653,14 @@ impl Database {
                     
                     // Get all systems in this constellation
                     let systems_in_constellation = sqlx::query(
-                        "SELECT id, name, x, y, z, region_id, constellation_id, faction_id
-                         FROM systems 
-                         WHERE constellation_id = ?"
+                        "SELECT id FROM systems WHERE constellation_id = ?"
                     )
                     .bind(constellation_id)
                     .fetch_all(&self.pool)
                     .await?;
 
                     let systems = systems_in_constellation.into_iter()
-                        .map(|row| SystemInfo {
-                            id: row.get("id"),
-                            name: row.get("name"),
-                            center: [
-                                row.get::<Option<f64>, _>("x").unwrap_or(0.0),
-                                row.get::<Option<f64>, _>("y").unwrap_or(0.0),
-                                row.get::<Option<f64>, _>("z").unwrap_or(0.0),
-                            ],
-                            region_id: row.get("region_id"),
-                            constellation_id: row.get("constellation_id"),
-                            faction_id: row.get("faction_id"),
-                            distance: None,
-                        })
+                        .filter_map(|row| index.system_info(row.get("id"), None))
                         .collect();
 
                     constellations.push(ConstellationWithSystems {
@@ -740,121 +690,6 @@ impl Database {
         }))
     }
 
-    /// Get gate connections for a specific system
-    pub async fn get_system_connections(&self, system_id: u32, connection_type: Option<&str>) -> Result<Vec<GateConnection>> {
-        // Use a simpler approach without dynamic query building
-        let rows = if let Some(conn_type) = connection_type {
-            sqlx::query(
-                "SELECT id, from_system_id, to_system_id, connection_type 
-                 FROM gate_connections 
-                 WHERE (from_system_id = ? OR to_system_id = ?) AND connection_type = ?"
-            )
-            .bind(system_id)
-            .bind(system_id)
-            .bind(conn_type)
-            .fetch_all(&self.pool)
-            .await?
-        } else {
-            sqlx::query(
-                "SELECT id, from_system_id, to_system_id, connection_type 
-                 FROM gate_connections 
-                 WHERE from_system_id = ? OR to_system_id = ?"
-            )
-            .bind(system_id)
-            .bind(system_id)
-            .fetch_all(&self.pool)
-            .await?
-        };
-
-        Ok(rows
-            .into_iter()
-            .map(|row| GateConnection {
-                id: row.get("id"),
-                from_system_id: row.get("from_system_id"),
-                to_system_id: row.get("to_system_id"),
-                connection_type: row.get("connection_type"),
-            })
-            .collect())
-    }
-
-        /// Get gate connections for multiple systems in bulk
-    pub async fn get_bulk_connections(&self, system_ids: &[u32], connection_type: Option<&str>) -> Result<Vec<SystemConnections>> {
-        if system_ids.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let placeholders = system_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        
-        let mut query = format!(
-            "SELECT id, from_system_id, to_system_id, connection_type 
-             FROM gate_connections 
-             WHERE from_system_id IN ({}) OR to_system_id IN ({})",
-            placeholders, placeholders
-        );
-
-        if connection_type.is_some() {
-            query.push_str(" AND connection_type = ?");
-        }
-
-        let mut query_builder = sqlx::query(&query);
-        
-        // Bind system_ids twice (for from_system_id and to_system_id)
-        for &system_id in system_ids {
-            query_builder = query_builder.bind(system_id);
-        }
-        for &system_id in system_ids {
-            query_builder = query_builder.bind(system_id);
-        }
-        
-        // Bind connection_type if provided
-        if let Some(conn_type) = connection_type {
-            query_builder = query_builder.bind(conn_type);
-        }
-
-        let rows = query_builder.fetch_all(&self.pool).await?;
-
-        // Group connections by system_id
-        let mut system_connections_map: std::collections::HashMap<u32, Vec<GateConnection>> = 
-            std::collections::HashMap::new();
-
-        for row in rows {
-            let connection = GateConnection {
-                id: row.get("id"),
-                from_system_id: row.get("from_system_id"),
-                to_system_id: row.get("to_system_id"),
-                connection_type: row.get("connection_type"),
-            };
-
-            // Add to both from and to systems
-            system_connections_map
-                .entry(connection.from_system_id)
-                .or_insert_with(Vec::new)
-                .push(connection.clone());
-            
-            if connection.from_system_id != connection.to_system_id {
-                system_connections_map
-                    .entry(connection.to_system_id)
-                    .or_insert_with(Vec::new)
-                    .push(connection);
-            }
-        }
-
-        // Convert to the response format
-        let mut result = Vec::new();
-        for &system_id in system_ids {
-            let connections = system_connections_map
-                .remove(&system_id)
-                .unwrap_or_else(Vec::new);
-
-            result.push(SystemConnections {
-                system_id,
-                connections,
-            });
-        }
-
-        Ok(result)
-    }
-
     /// Get all gate connections with pagination
     pub async fn get_all_connections(&self, limit: usize, offset: usize, connection_type: Option<&str>) -> Result<(Vec<GateConnection>, usize)> {
         // Get total count first
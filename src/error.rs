@@ -10,6 +10,12 @@ use tracing::error;
 #[derive(Debug)]
 pub enum ApiError {
     SystemNotFound(String),
+    RegionNotFound(u32),
+    ConstellationNotFound(u32),
+    TileNotFound(String),
+    RouteNotFound(String),
+    /// No route satisfies the requested constraints; lists the blocking ones
+    RouteBlocked { message: String, constraints: Vec<String> },
     #[allow(dead_code)]
     InvalidInput(String),
     DatabaseError(sqlx::Error),
@@ -28,16 +34,48 @@ struct ErrorResponse {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_type, message) = match self {
+        let (status, error_type, message, details) = match self {
             ApiError::SystemNotFound(name) => (
                 StatusCode::NOT_FOUND,
                 "system_not_found",
                 format!("System '{}' was not found", name),
+                None,
+            ),
+            ApiError::RegionNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "region_not_found",
+                format!("Region {} was not found", id),
+                None,
+            ),
+            ApiError::ConstellationNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "constellation_not_found",
+                format!("Constellation {} was not found", id),
+                None,
+            ),
+            ApiError::TileNotFound(tile) => (
+                StatusCode::NOT_FOUND,
+                "tile_not_found",
+                format!("Map tile {} does not exist", tile),
+                None,
+            ),
+            ApiError::RouteNotFound(msg) => (
+                StatusCode::NOT_FOUND,
+                "route_not_found",
+                msg,
+                None,
+            ),
+            ApiError::RouteBlocked { message, constraints } => (
+                StatusCode::NOT_FOUND,
+                "route_blocked",
+                message,
+                Some(format!("Blocked by: {}", constraints.join(", "))),
             ),
             ApiError::InvalidInput(msg) => (
                 StatusCode::BAD_REQUEST,
                 "invalid_input",
                 msg,
+                None,
             ),
             ApiError::DatabaseError(ref e) => {
                 error!("Database error: {:?}", e);
@@ -45,6 +83,7 @@ impl IntoResponse for ApiError {
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "database_error",
                     "A database error occurred".to_string(),
+                    None,
                 )
             },
             ApiError::InternalError(ref e) => {
@@ -53,6 +92,7 @@ impl IntoResponse for ApiError {
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "internal_error",
                     "An internal server error occurred".to_string(),
+                    None,
                 )
             },
         };
@@ -60,7 +100,7 @@ impl IntoResponse for ApiError {
         let response = ErrorResponse {
             error: error_type.to_string(),
             message,
-            details: None,
+            details,
             request_id: None, // TODO: Extract from request context if needed
         };
 
@@ -72,6 +112,13 @@ impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ApiError::SystemNotFound(name) => write!(f, "System not found: {}", name),
+            ApiError::RegionNotFound(id) => write!(f, "Region not found: {}", id),
+            ApiError::ConstellationNotFound(id) => write!(f, "Constellation not found: {}", id),
+            ApiError::TileNotFound(tile) => write!(f, "Tile not found: {}", tile),
+            ApiError::RouteNotFound(msg) => write!(f, "Route not found: {}", msg),
+            ApiError::RouteBlocked { message, constraints } => {
+                write!(f, "Route blocked: {} ({})", message, constraints.join(", "))
+            }
             ApiError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             ApiError::DatabaseError(e) => write!(f, "Database error: {}", e),
             ApiError::InternalError(e) => write!(f, "Internal error: {}", e),
@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use rustc_hash::FxHashMap;
+use tracing::info;
+
+use crate::{
+    coordinates::Distance,
+    error::{ApiError, ApiResult},
+    handlers::run_blocking,
+    models::{ClusteredSystem, ClustersQuery, ClustersResponse, SystemCluster},
+    AppState,
+};
+
+/// Largest eps or density radius accepted, in light-years. Every system's
+/// neighbourhood is fetched at least once, so the work grows with the cube
+/// of this; 10 ly matches the longest jump drive range
+const MAX_RADIUS_LY: f64 = 10.0;
+
+/// Cluster systems by density (DBSCAN) and report each system's local density
+#[utoipa::path(
+    get,
+    path = "/systems/clusters",
+    params(ClustersQuery),
+    responses(
+        (status = 200, description = "Dense clusters and one page of the systems considered", body = ClustersResponse),
+        (status = 400, description = "Invalid parameters"),
+        (status = 404, description = "Region not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "systems"
+)]
+pub async fn systems_clusters(
+    Query(params): Query<ClustersQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ClustersResponse>> {
+    let density_radius = params.density_radius.unwrap_or(params.eps);
+    for (name, value) in [("eps", params.eps), ("density_radius", density_radius)] {
+        if !(value > 0.0 && value <= MAX_RADIUS_LY) {
+            return Err(ApiError::InvalidInput(format!(
+                "{} must be greater than 0 and at most {} ly",
+                name, MAX_RADIUS_LY
+            )));
+        }
+    }
+    if params.min_points == 0 {
+        return Err(ApiError::InvalidInput("min_points must be at least 1".to_string()));
+    }
+
+    let include_densities = params.include_densities.unwrap_or(false);
+    let limit = params.limit.unwrap_or(1000).min(5000); // Cap at 5000 systems
+    let offset = params.offset.unwrap_or(0);
+
+    run_blocking(&state.spatial_index, move |index| {
+        let members = match params.region_id {
+            Some(region_id) => Some(
+                index
+                    .get_region(region_id)
+                    .ok_or(ApiError::RegionNotFound(region_id))?
+                    .solar_system_ids
+                    .as_slice(),
+            ),
+            None => None,
+        };
+
+        info!(
+            "Clustering systems: eps={} ly, min_points={}, region={:?}",
+            params.eps, params.min_points, params.region_id
+        );
+
+        let clustering = index.cluster_systems(
+            members,
+            Distance::from_light_years(params.eps).to_meters(),
+            params.min_points,
+        );
+
+        let mut considered: Vec<u32> = clustering
+            .clusters
+            .iter()
+            .flat_map(|cluster| cluster.systems.iter().copied())
+            .chain(clustering.noise.iter().copied())
+            .collect();
+        considered.sort_unstable();
+
+        let mut cluster_of = FxHashMap::default();
+        let clusters = clustering
+            .clusters
+            .iter()
+            .enumerate()
+            .map(|(rank, cluster)| {
+                let count = cluster.systems.len() as f64;
+                let mut center = [0.0; 3];
+                for id in &cluster.systems {
+                    cluster_of.insert(*id, rank as u32);
+                    if let Some(system) = index.get_system(*id) {
+                        for (total, value) in center.iter_mut().zip(system.center) {
+                            *total += value / count;
+                        }
+                    }
+                }
+                SystemCluster {
+                    id: rank as u32,
+                    size: cluster.systems.len(),
+                    core_count: cluster.core.len(),
+                    center,
+                }
+            })
+            .collect();
+
+        // Densities are counted for the same page of the same ID-sorted systems
+        let densities = if include_densities {
+            index.local_density(members, Distance::from_light_years(density_radius).to_meters(), offset, limit)
+        } else {
+            Vec::new()
+        };
+        let mut densities = densities.into_iter();
+        let systems = considered
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|&system_id| ClusteredSystem {
+                system_id,
+                cluster: cluster_of.get(&system_id).copied(),
+                neighbours: densities.next().map(|(_, neighbours)| neighbours),
+            })
+            .collect();
+
+        Ok(ClustersResponse {
+            eps: params.eps,
+            min_points: params.min_points,
+            density_radius,
+            clusters,
+            noise_count: clustering.noise.len(),
+            systems,
+            total_systems: considered.len(),
+            offset,
+            limit,
+        })
+    })
+    .await
+    .map(Json)
+}
@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    models::{MapTileResponse, SystemMapData},
+    AppState,
+};
+
+/// Get one octree tile of the galaxy map
+///
+/// Start at `/map/tiles/0/0/0/0` and request the listed children while
+/// zooming in. Coarse tiles hold a capped sample spread evenly across the
+/// tile; leaf tiles hold every system inside them.
+#[utoipa::path(
+    get,
+    path = "/map/tiles/{level}/{x}/{y}/{z}",
+    params(
+        ("level" = u8, Path, description = "Octree level; level 0 is the whole galaxy"),
+        ("x" = u32, Path, description = "Tile X index within the level"),
+        ("y" = u32, Path, description = "Tile Y index within the level"),
+        ("z" = u32, Path, description = "Tile Z index within the level")
+    ),
+    responses(
+        (status = 200, description = "Systems in the tile", body = MapTileResponse),
+        (status = 404, description = "Tile is empty or below a leaf"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "map"
+)]
+pub async fn map_tile(
+    Path((level, x, y, z)): Path<(u8, u32, u32, u32)>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<MapTileResponse>> {
+    info!("Map tile request: {}/{}/{}/{}", level, x, y, z);
+
+    let tiles = state.spatial_index.map_tiles();
+    let key = (level, x, y, z);
+    let tile = tiles
+        .get(key)
+        .ok_or_else(|| ApiError::TileNotFound(format!("{}/{}/{}/{}", level, x, y, z)))?;
+
+    let systems = tile
+        .systems
+        .iter()
+        .filter_map(|&id| {
+            let system = state.spatial_index.get_system(id)?;
+            let name = state.spatial_index.get_system_name(id)?.clone();
+            Some(SystemMapData {
+                id,
+                name,
+                center: system.center,
+            })
+        })
+        .collect();
+
+    let children = (0..8u32)
+        .filter(|octant| tile.children & (1 << octant) != 0)
+        .map(|octant| [x * 2 + (octant & 1), y * 2 + ((octant >> 1) & 1), z * 2 + ((octant >> 2) & 1)])
+        .collect();
+
+    let (min, max) = tiles.bounds(key);
+    Ok(Json(MapTileResponse {
+        level,
+        x,
+        y,
+        z,
+        min,
+        max,
+        leaf: tile.leaf,
+        total_systems: tile.total,
+        systems,
+        children,
+    }))
+}
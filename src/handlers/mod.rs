@@ -1,3 +1,54 @@
+use rustc_hash::FxHashSet;
+use std::{str::FromStr, sync::Arc};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    spatial::SpatialIndex,
+};
+
+pub mod density;
 pub mod health;
+pub mod map;
+pub mod network;
+pub mod regions;
+pub mod routing;
+pub mod search;
 pub mod systems;
-pub mod type_names; 
\ No newline at end of file
+pub mod type_names;
+pub mod volumes;
+
+/// Run route searches and other index-wide work on the blocking thread pool,
+/// keeping it off the async runtime's worker threads
+pub(crate) async fn run_blocking<T, F>(index: &Arc<SpatialIndex>, work: F) -> ApiResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&SpatialIndex) -> ApiResult<T> + Send + 'static,
+{
+    let index = Arc::clone(index);
+    tokio::task::spawn_blocking(move || work(&index))
+        .await
+        .map_err(|e| ApiError::InternalError(e.into()))?
+}
+
+/// Split a comma-separated query value, skipping empty entries
+pub(crate) fn split_list(value: &Option<String>) -> impl Iterator<Item = &str> {
+    value
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+pub(crate) fn parse_id_list<T>(name: &str, value: &Option<String>) -> ApiResult<FxHashSet<T>>
+where
+    T: FromStr + Eq + std::hash::Hash,
+{
+    split_list(value)
+        .map(|item| {
+            item.parse().map_err(|_| {
+                ApiError::InvalidInput(format!("{} contains an invalid ID: '{}'", name, item))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,195 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    coordinates::Distance,
+    models::{
+        ChokepointsQuery, ChokepointsResponse, ComponentsQuery, ComponentsResponse, GateBridge,
+        GateComponentInfo, HubsQuery, HubsResponse, JumpEntryPoint,
+    },
+    spatial::network::Chokepoints,
+    AppState,
+};
+
+/// Articulation points and bridges of the gate network, globally or within one region
+#[utoipa::path(
+    get,
+    path = "/network/chokepoints",
+    params(ChokepointsQuery),
+    responses(
+        (status = 200, description = "One page each of the systems and gates whose loss splits the network", body = ChokepointsResponse),
+        (status = 404, description = "Region not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "network"
+)]
+pub async fn network_chokepoints(
+    Query(params): Query<ChokepointsQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ChokepointsResponse>> {
+    let index = &state.spatial_index;
+    let network = index.network_analysis();
+    let limit = params.limit.unwrap_or(100).min(1000); // Cap at 1000 of each
+    let offset = params.offset.unwrap_or(0);
+
+    info!(
+        "Listing gate network chokepoints (region: {:?}, limit: {}, offset: {})",
+        params.region_id, limit, offset
+    );
+
+    let empty = Chokepoints::default();
+    let chokepoints = match params.region_id {
+        None => &network.global,
+        Some(region_id) => match network.regions.get(&region_id) {
+            Some(chokepoints) => chokepoints,
+            // A known region without systems has nothing to split
+            None if index.get_region(region_id).is_some() => &empty,
+            None => return Err(ApiError::RegionNotFound(region_id)),
+        },
+    };
+
+    let articulation_points = chokepoints
+        .articulation_points
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|&id| index.system_info(id, None))
+        .collect();
+    let bridges = chokepoints
+        .bridges
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|&(from, to)| {
+            Some(GateBridge {
+                from: index.system_info(from, None)?,
+                to: index.system_info(to, None)?,
+            })
+        })
+        .collect();
+
+    Ok(Json(ChokepointsResponse {
+        region_id: params.region_id,
+        articulation_points,
+        bridges,
+        total_articulation_points: chokepoints.articulation_points.len(),
+        total_bridges: chokepoints.bridges.len(),
+        offset,
+        limit,
+    }))
+}
+
+/// Rank systems by how central they are to the gate network
+#[utoipa::path(
+    get,
+    path = "/systems/hubs",
+    params(HubsQuery),
+    responses(
+        (status = 200, description = "Systems in descending order of centrality", body = HubsResponse),
+        (status = 404, description = "Region not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "network"
+)]
+pub async fn systems_hubs(
+    Query(params): Query<HubsQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<HubsResponse>> {
+    let index = &state.spatial_index;
+    let limit = params.limit.unwrap_or(20).min(500); // Cap at 500 systems
+    let metric = params.metric.unwrap_or_default();
+
+    info!(
+        "Ranking {:?} hubs (region: {:?}, limit: {})",
+        metric, params.region_id, limit
+    );
+
+    if let Some(region_id) = params.region_id {
+        if index.get_region(region_id).is_none() {
+            return Err(ApiError::RegionNotFound(region_id));
+        }
+    }
+
+    let hubs = index
+        .rank_hubs(params.region_id, metric)
+        .into_iter()
+        .take(limit)
+        .filter_map(|(id, _)| index.system_info(id, None))
+        .collect();
+
+    Ok(Json(HubsResponse {
+        region_id: params.region_id,
+        metric,
+        hubs,
+    }))
+}
+
+/// Connected components of the gate network, including gate-less systems
+#[utoipa::path(
+    get,
+    path = "/network/components",
+    params(ComponentsQuery),
+    responses(
+        (status = 200, description = "Components largest first, with the nearest jump entry from the main component", body = ComponentsResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "network"
+)]
+pub async fn network_components(
+    Query(params): Query<ComponentsQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ComponentsResponse>> {
+    let index = &state.spatial_index;
+    let components = &index.network_analysis().components;
+    let limit = params.limit.unwrap_or(100).min(1000); // Cap at 1000 components
+    let offset = params.offset.unwrap_or(0);
+
+    info!("Listing gate network components: limit={}, offset={}", limit, offset);
+
+    let page = components
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(limit)
+        .map(|(i, component)| {
+            let is_main = i == 0;
+            let systems = if is_main {
+                Vec::new()
+            } else {
+                component
+                    .systems
+                    .iter()
+                    .filter_map(|&id| index.system_info(id, None))
+                    .collect()
+            };
+            let entry_point = component.entry.and_then(|entry| {
+                Some(JumpEntryPoint {
+                    from: index.system_info(entry.from, None)?,
+                    to: index.system_info(entry.to, None)?,
+                    distance_ly: Distance::from_meters(entry.distance).to_ly(),
+                })
+            });
+
+            GateComponentInfo {
+                index: i,
+                size: component.systems.len(),
+                is_main,
+                region_ids: component.regions.clone(),
+                systems,
+                entry_point,
+            }
+        })
+        .collect();
+
+    Ok(Json(ComponentsResponse {
+        components: page,
+        total_components: components.len(),
+        gate_less_systems: components.iter().filter(|c| c.systems.len() == 1).count(),
+        offset,
+        limit,
+    }))
+}
@@ -0,0 +1,235 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    coordinates::Distance,
+    error::{ApiError, ApiResult},
+    models::{
+        ConstellationDetails, GeometryInfo, HullInfo, HullVertex, LayoutPosition, LayoutQuery,
+        RegionDetails, RegionLayoutResponse, TerritoryLookupQuery, TerritoryLookupResponse,
+        TerritoryMatchInfo,
+    },
+    handlers::volumes::point_in_meters,
+    spatial::{
+        geometry::{Geometry, TerritoryMatch},
+        SpatialIndex,
+    },
+    AppState,
+};
+
+fn geometry_info(index: &SpatialIndex, geometry: &Geometry) -> GeometryInfo {
+    let vertices = geometry
+        .hull
+        .vertices
+        .iter()
+        .filter_map(|&system_id| {
+            Some(HullVertex {
+                system_id,
+                position: index.get_system(system_id)?.center,
+            })
+        })
+        .collect();
+
+    GeometryInfo {
+        center: geometry.centroid,
+        min: geometry.min,
+        max: geometry.max,
+        radius: Distance::from_meters(geometry.radius).to_ly(),
+        hull: HullInfo {
+            vertices,
+            faces: geometry.hull.faces.clone(),
+        },
+    }
+}
+
+/// Get a region's members, centre, bounds and convex hull
+#[utoipa::path(
+    get,
+    path = "/regions/{id}",
+    params(
+        ("id" = u32, Path, description = "Region ID")
+    ),
+    responses(
+        (status = 200, description = "Region details", body = RegionDetails),
+        (status = 404, description = "Region not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "regions"
+)]
+pub async fn region_details(
+    Path(region_id): Path<u32>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<RegionDetails>> {
+    info!("Region details request: {}", region_id);
+
+    let index = &state.spatial_index;
+    let region = index
+        .get_region(region_id)
+        .ok_or(ApiError::RegionNotFound(region_id))?;
+
+    Ok(Json(RegionDetails {
+        id: region_id,
+        name: index.get_region_name(region_id).cloned(),
+        solar_system_ids: region.solar_system_ids.clone(),
+        constellation_ids: region.constellation_ids.clone(),
+        geometry: index
+            .region_geometry(region_id)
+            .map(|geometry| geometry_info(index, geometry)),
+    }))
+}
+
+/// Get a constellation's members, centre, bounds and convex hull
+#[utoipa::path(
+    get,
+    path = "/constellations/{id}",
+    params(
+        ("id" = u32, Path, description = "Constellation ID")
+    ),
+    responses(
+        (status = 200, description = "Constellation details", body = ConstellationDetails),
+        (status = 404, description = "Constellation not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "regions"
+)]
+pub async fn constellation_details(
+    Path(constellation_id): Path<u32>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ConstellationDetails>> {
+    info!("Constellation details request: {}", constellation_id);
+
+    let index = &state.spatial_index;
+    let constellation = index
+        .get_constellation(constellation_id)
+        .ok_or(ApiError::ConstellationNotFound(constellation_id))?;
+
+    Ok(Json(ConstellationDetails {
+        id: constellation_id,
+        name: constellation.name.clone(),
+        region_id: constellation.region_id,
+        region_name: index.get_region_name(constellation.region_id).cloned(),
+        solar_system_ids: constellation.solar_system_ids.clone(),
+        geometry: index
+            .constellation_geometry(constellation_id)
+            .map(|geometry| geometry_info(index, geometry)),
+    }))
+}
+
+/// Get a precomputed 2D layout of a region's systems
+#[utoipa::path(
+    get,
+    path = "/regions/{id}/layout",
+    params(
+        ("id" = u32, Path, description = "Region ID"),
+        LayoutQuery
+    ),
+    responses(
+        (status = 200, description = "Projected positions of the region's systems in light-years", body = RegionLayoutResponse),
+        (status = 404, description = "Region not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "regions"
+)]
+pub async fn region_layout(
+    Path(region_id): Path<u32>,
+    Query(params): Query<LayoutQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<RegionLayoutResponse>> {
+    let method = params.method.unwrap_or_default();
+    info!("Region layout request: region {} ({:?})", region_id, method);
+
+    let index = &state.spatial_index;
+    if index.get_region(region_id).is_none() {
+        return Err(ApiError::RegionNotFound(region_id));
+    }
+
+    let mut systems = Vec::new();
+    let mut gates = Vec::new();
+    let mut min = [f64::INFINITY; 2];
+    let mut max = [f64::NEG_INFINITY; 2];
+
+    // Regions without systems have no layout
+    if let Some(layout) = index.region_layout(region_id) {
+        for (&id, &position) in layout.systems.iter().zip(layout.positions(method)) {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(position[axis]);
+                max[axis] = max[axis].max(position[axis]);
+            }
+            systems.push(LayoutPosition {
+                id,
+                name: index.get_system_name(id).cloned().unwrap_or_default(),
+                position,
+            });
+        }
+        gates = layout.gates.iter().map(|&(a, b)| [a, b]).collect();
+    }
+    if systems.is_empty() {
+        min = [0.0; 2];
+        max = [0.0; 2];
+    }
+
+    Ok(Json(RegionLayoutResponse {
+        region_id,
+        region_name: index.get_region_name(region_id).cloned(),
+        method,
+        systems,
+        gates,
+        min,
+        max,
+    }))
+}
+
+fn territory_match_info(
+    index: &SpatialIndex,
+    matched: TerritoryMatch,
+    name: Option<String>,
+) -> Option<TerritoryMatchInfo> {
+    Some(TerritoryMatchInfo {
+        id: matched.id,
+        name,
+        inside_hull: matched.inside_hull,
+        nearest_system: index.system_info(
+            matched.nearest_system,
+            Some(Distance::from_meters(matched.distance).to_ly()),
+        )?,
+    })
+}
+
+/// Find the region and constellation an arbitrary point falls in
+#[utoipa::path(
+    get,
+    path = "/territories/locate",
+    params(TerritoryLookupQuery),
+    responses(
+        (status = 200, description = "Containing (or nearest) region and constellation", body = TerritoryLookupResponse),
+        (status = 400, description = "Invalid coordinates"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "regions"
+)]
+pub async fn locate_territory(
+    Query(params): Query<TerritoryLookupQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<TerritoryLookupResponse>> {
+    let point = point_in_meters(params.x, params.y, params.z, params.unit)?;
+    info!("Territory lookup at {:?}", point);
+
+    let index = &state.spatial_index;
+    let (region, constellation) = index.locate_territory(point);
+
+    Ok(Json(TerritoryLookupResponse {
+        point,
+        region: region.and_then(|region| {
+            territory_match_info(index, region, index.get_region_name(region.id).cloned())
+        }),
+        constellation: constellation.and_then(|constellation| {
+            let name = index
+                .get_constellation(constellation.id)
+                .map(|constellation| constellation.name.clone());
+            territory_match_info(index, constellation, name)
+        }),
+    }))
+}
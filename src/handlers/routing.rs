@@ -0,0 +1,544 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use rustc_hash::FxHashSet;
+use tracing::info;
+
+use crate::{
+    coordinates::{Coordinate3D, Distance},
+    error::{ApiError, ApiResult},
+    handlers::{parse_id_list, run_blocking, split_list},
+    models::{
+        AlternativeRoute, AlternativeRoutesQuery, AlternativeRoutesResponse, PilotCost,
+        ReachableQuery, ReachableSystem, ReachableSystemsResponse, RendezvousCandidate,
+        RendezvousQuery, RendezvousResponse, RouteHop, RouteMode, RouteParams, RouteQuery,
+        RouteResponse, TourQuery, TourResponse,
+    },
+    spatial::{
+        routing::{ConstraintKind, EdgeWeights, RouteConstraints, RouteOptions, RoutePlan},
+        SpatialIndex, SystemId,
+    },
+    AppState,
+};
+
+/// Longest jump drive range a routing query may ask for. Each jump hop
+/// expands every system within range, so the search grows with the cube of
+/// this; no ship's jump drive reaches further
+const MAX_JUMP_LY: f64 = 10.0;
+
+/// Most stops a single tour request may visit (legs are planned pairwise)
+const MAX_TOUR_STOPS: usize = 32;
+
+/// Largest total hop budget a reachability query may ask for; with a jump
+/// drive range a few dozen hops already span most of the map
+const MAX_REACHABLE_JUMPS: u32 = 20;
+
+/// Most pilots a single rendezvous request may place
+const MAX_RENDEZVOUS_PILOTS: usize = 32;
+
+/// Find the best route between two systems by stargate, jump drive or both
+#[utoipa::path(
+    get,
+    path = "/systems/route",
+    params(RouteQuery, RouteParams),
+    responses(
+        (status = 200, description = "Best route, hop by hop (distances in light-years)", body = RouteResponse),
+        (status = 400, description = "Invalid routing options"),
+        (status = 404, description = "System not found or no route exists"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "routing"
+)]
+pub async fn systems_route(
+    Query(params): Query<RouteQuery>,
+    Query(route_params): Query<RouteParams>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<RouteResponse>> {
+    run_blocking(&state.spatial_index, move |index| {
+        let options = route_options(index, &route_params)?;
+
+        info!(
+            "Planning {:?} route from '{}' to '{}' (optimize: {:?})",
+            options.mode, params.from, params.to, options.optimize
+        );
+
+        let from = resolve(index, &params.from)?;
+        let to = resolve(index, &params.to)?;
+
+        let plan = index
+            .find_route(from, to, &options)
+            .ok_or_else(|| no_route_error(index, from, to, &options, &params.from, &params.to))?;
+
+        build_route_response(index, &plan)
+    })
+    .await
+    .map(Json)
+}
+
+/// List every system reachable from a system within a number of jumps
+#[utoipa::path(
+    get,
+    path = "/systems/reachable",
+    params(ReachableQuery),
+    responses(
+        (status = 200, description = "Reachable systems with hop counts and previous hops (distances in light-years)", body = ReachableSystemsResponse),
+        (status = 400, description = "Invalid jump budget or range"),
+        (status = 404, description = "System not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "routing"
+)]
+pub async fn systems_reachable(
+    Query(params): Query<ReachableQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ReachableSystemsResponse>> {
+    run_blocking(&state.spatial_index, move |index| {
+        if params.max_jumps > MAX_REACHABLE_JUMPS {
+            return Err(ApiError::InvalidInput(format!(
+                "max_jumps may be at most {}",
+                MAX_REACHABLE_JUMPS
+            )));
+        }
+        let route_params = RouteParams {
+            mode: params.max_jump_ly.map(|_| RouteMode::Mixed),
+            max_jump_ly: params.max_jump_ly,
+            ..RouteParams::default()
+        };
+        let options = route_options(index, &route_params)?;
+        let limit = params.limit.unwrap_or(500).min(1000); // Cap at 1000 systems
+        let offset = params.offset.unwrap_or(0);
+
+        info!(
+            "Finding systems reachable from '{}' within {} hops (jump range: {:?} ly, limit: {}, offset: {})",
+            params.name, params.max_jumps, params.max_jump_ly, limit, offset
+        );
+
+        let origin_id = resolve(index, &params.name)?;
+        let origin = index.system_info(origin_id, Some(0.0)).ok_or_else(|| {
+            ApiError::InternalError(anyhow::anyhow!(
+                "System {} resolved but not in the index",
+                origin_id
+            ))
+        })?;
+        let origin_position = Coordinate3D::from_meters_array(origin.center);
+
+        // Every reached system but the origin, which has no previous hop
+        let reached: Vec<_> = index
+            .reachable_within(origin_id, params.max_jumps, &options)
+            .into_iter()
+            .filter_map(|reached| Some((reached.system_id, reached.hops, reached.previous?, reached.via?)))
+            .collect();
+        let total_found = reached.len();
+
+        let reachable_systems: Vec<ReachableSystem> = reached
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|(system_id, hops, previous_system_id, via)| {
+                let mut system = index.system_info(system_id, None)?;
+                system.distance = Some(
+                    origin_position
+                        .distance_to(&Coordinate3D::from_meters_array(system.center))
+                        .to_ly(),
+                );
+                Some(ReachableSystem {
+                    system,
+                    hops,
+                    previous_system_id,
+                    via,
+                })
+            })
+            .collect();
+
+        Ok(ReachableSystemsResponse {
+            origin,
+            reachable_systems,
+            max_jumps: params.max_jumps,
+            max_jump_ly: params.max_jump_ly,
+            total_found,
+            offset,
+            limit,
+        })
+    })
+    .await
+    .map(Json)
+}
+
+/// List the best route and its cheapest loopless alternatives between two systems
+#[utoipa::path(
+    get,
+    path = "/systems/route/alternatives",
+    params(AlternativeRoutesQuery, RouteParams),
+    responses(
+        (status = 200, description = "Up to k routes in increasing cost order, best first", body = AlternativeRoutesResponse),
+        (status = 400, description = "Invalid routing options"),
+        (status = 404, description = "System not found or no route exists"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "routing"
+)]
+pub async fn systems_route_alternatives(
+    Query(params): Query<AlternativeRoutesQuery>,
+    Query(route_params): Query<RouteParams>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<AlternativeRoutesResponse>> {
+    run_blocking(&state.spatial_index, move |index| {
+        let options = route_options(index, &route_params)?;
+        let k = params.k.unwrap_or(3).clamp(1, 10); // Cap at 10 routes
+
+        info!(
+            "Planning {} alternative {:?} routes from '{}' to '{}'",
+            k, options.mode, params.from, params.to
+        );
+
+        let from = resolve(index, &params.from)?;
+        let to = resolve(index, &params.to)?;
+
+        let plans = index.find_alternative_routes(from, to, k, &options);
+        let Some(best) = plans.first() else {
+            return Err(no_route_error(index, from, to, &options, &params.from, &params.to));
+        };
+        let best_systems: FxHashSet<SystemId> = best.systems.iter().copied().collect();
+
+        let routes = plans
+            .iter()
+            .enumerate()
+            .map(|(i, plan)| {
+                let RouteResponse {
+                    route,
+                    jumps,
+                    total_distance_ly,
+                    total_cost,
+                } = build_route_response(index, plan)?;
+                let differs_from_best = plan
+                    .systems
+                    .iter()
+                    .filter(|id| !best_systems.contains(id))
+                    .filter_map(|&id| index.system_info(id, None))
+                    .collect();
+
+                Ok(AlternativeRoute {
+                    rank: i + 1,
+                    route,
+                    jumps,
+                    total_distance_ly,
+                    total_cost,
+                    differs_from_best,
+                })
+            })
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        Ok(AlternativeRoutesResponse { routes })
+    })
+    .await
+    .map(Json)
+}
+
+/// Order a list of waypoints to approximately minimise total travel, with optional fixed start and end
+#[utoipa::path(
+    get,
+    path = "/systems/route/tour",
+    params(TourQuery, RouteParams),
+    responses(
+        (status = 200, description = "Approximate best visiting order and the full route through it", body = TourResponse),
+        (status = 400, description = "Invalid stops or routing options"),
+        (status = 404, description = "System not found or two stops cannot be connected"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "routing"
+)]
+pub async fn systems_route_tour(
+    Query(params): Query<TourQuery>,
+    Query(route_params): Query<RouteParams>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<TourResponse>> {
+    run_blocking(&state.spatial_index, move |index| {
+        let options = route_options(index, &route_params)?;
+
+        let stops = split_list(&Some(params.systems))
+            .map(|name| resolve(index, name))
+            .collect::<ApiResult<Vec<_>>>()?;
+        let start = params.start.as_deref().map(|name| resolve(index, name)).transpose()?;
+        let end = params.end.as_deref().map(|name| resolve(index, name)).transpose()?;
+
+        if stops.is_empty() {
+            return Err(ApiError::InvalidInput("systems must list at least one stop".to_string()));
+        }
+        if stops.len() > MAX_TOUR_STOPS {
+            return Err(ApiError::InvalidInput(format!(
+                "A tour may visit at most {} systems",
+                MAX_TOUR_STOPS
+            )));
+        }
+
+        info!(
+            "Planning {:?} tour over {} stops (start: {:?}, end: {:?})",
+            options.mode,
+            stops.len(),
+            params.start,
+            params.end
+        );
+
+        let tour = index.plan_tour(&stops, start, end, &options).map_err(|leg| {
+            let name = |id| index.get_system_name(id).cloned().unwrap_or_else(|| id.to_string());
+            no_route_error(index, leg.from, leg.to, &options, &name(leg.from), &name(leg.to))
+        })?;
+
+        let stops = tour
+            .stops
+            .iter()
+            .filter_map(|&id| index.system_info(id, None))
+            .collect();
+        let RouteResponse {
+            route,
+            jumps,
+            total_distance_ly,
+            total_cost,
+        } = build_route_response(index, &tour.route)?;
+
+        Ok(TourResponse {
+            stops,
+            route,
+            jumps,
+            total_distance_ly,
+            total_cost,
+            approximate: true,
+        })
+    })
+    .await
+    .map(Json)
+}
+
+/// Find the best systems for several pilots to meet in, minimising the longest or combined trip
+#[utoipa::path(
+    get,
+    path = "/systems/rendezvous",
+    params(RendezvousQuery, RouteParams),
+    responses(
+        (status = 200, description = "Best meeting systems first, with each pilot's cost to arrive", body = RendezvousResponse),
+        (status = 400, description = "Invalid starting systems or routing options"),
+        (status = 404, description = "System not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "routing"
+)]
+pub async fn systems_rendezvous(
+    Query(params): Query<RendezvousQuery>,
+    Query(route_params): Query<RouteParams>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<RendezvousResponse>> {
+    run_blocking(&state.spatial_index, move |index| {
+        let options = route_options(index, &route_params)?;
+        let objective = params.objective.unwrap_or_default();
+        let pool = params.candidates.unwrap_or(250).min(2000); // Cap at 2000 candidates
+        let limit = params.limit.unwrap_or(10).min(100); // Cap at 100 results
+
+        let starts = split_list(&Some(params.systems))
+            .map(|name| resolve(index, name))
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        if starts.is_empty() {
+            return Err(ApiError::InvalidInput("systems must list at least one starting system".to_string()));
+        }
+        if starts.len() > MAX_RENDEZVOUS_PILOTS {
+            return Err(ApiError::InvalidInput(format!(
+                "A rendezvous may include at most {} pilots",
+                MAX_RENDEZVOUS_PILOTS
+            )));
+        }
+
+        info!(
+            "Finding {:?} rendezvous for {} pilots ({:?} routing, optimize: {:?})",
+            objective,
+            starts.len(),
+            options.mode,
+            options.optimize
+        );
+
+        let candidates = index
+            .find_rendezvous(&starts, pool, objective, limit, &options)
+            .into_iter()
+            .filter_map(|rendezvous| {
+                Some(RendezvousCandidate {
+                    system: index.system_info(rendezvous.system_id, None)?,
+                    max_cost: rendezvous.max_cost,
+                    total_cost: rendezvous.total_cost,
+                    pilot_costs: starts
+                        .iter()
+                        .zip(rendezvous.costs)
+                        .map(|(&from_system_id, cost)| PilotCost { from_system_id, cost })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        Ok(RendezvousResponse {
+            starts: starts.iter().filter_map(|&id| index.system_info(id, None)).collect(),
+            objective,
+            candidates,
+        })
+    })
+    .await
+    .map(Json)
+}
+
+/// Build the error for a failed route, naming the constraints that block it
+fn no_route_error(
+    index: &SpatialIndex,
+    from: SystemId,
+    to: SystemId,
+    options: &RouteOptions,
+    from_name: &str,
+    to_name: &str,
+) -> ApiError {
+    let blocking = index.find_blocking_constraints(from, to, options);
+    if blocking.is_empty() {
+        return ApiError::RouteNotFound(format!(
+            "No {} from '{}' to '{}'",
+            describe_mode(options),
+            from_name,
+            to_name
+        ));
+    }
+
+    ApiError::RouteBlocked {
+        message: format!(
+            "'{}' is unreachable from '{}' by {} under the requested constraints",
+            to_name,
+            from_name,
+            describe_mode(options)
+        ),
+        constraints: blocking
+            .into_iter()
+            .map(|kind| constraint_param(kind).to_string())
+            .collect(),
+    }
+}
+
+/// Query parameter that sets a constraint category
+fn constraint_param(kind: ConstraintKind) -> &'static str {
+    match kind {
+        ConstraintKind::System => "avoid_systems",
+        ConstraintKind::Constellation => "avoid_constellations",
+        ConstraintKind::Region => "avoid_regions",
+        ConstraintKind::SecurityClass => "avoid_security_classes",
+        ConstraintKind::Faction => "avoid_factions",
+    }
+}
+
+/// Validate query options, convert light-year ranges to meters and resolve avoid lists
+fn route_options(index: &SpatialIndex, params: &RouteParams) -> ApiResult<RouteOptions> {
+    let mode = params.mode.unwrap_or_default();
+
+    let max_jump_range = match params.max_jump_ly {
+        Some(ly) if !(ly > 0.0 && ly <= MAX_JUMP_LY) => {
+            return Err(ApiError::InvalidInput(format!(
+                "max_jump_ly must be greater than 0 and at most {} ly",
+                MAX_JUMP_LY
+            )));
+        }
+        Some(ly) => Some(Distance::from_light_years(ly).to_meters()),
+        None => None,
+    };
+
+    if mode != RouteMode::Gate && max_jump_range.is_none() {
+        return Err(ApiError::InvalidInput(
+            "max_jump_ly is required for jump and mixed routing".to_string(),
+        ));
+    }
+
+    let defaults = EdgeWeights::default();
+    let weights = EdgeWeights {
+        stargate: edge_weight("stargate_weight", params.stargate_weight, defaults.stargate)?,
+        jump_bridge: edge_weight("jump_bridge_weight", params.jump_bridge_weight, defaults.jump_bridge)?,
+        wormhole: edge_weight("wormhole_weight", params.wormhole_weight, defaults.wormhole)?,
+        jump: edge_weight("jump_weight", params.jump_weight, defaults.jump)?,
+    };
+
+    let constraints = RouteConstraints {
+        systems: split_list(&params.avoid_systems)
+            .map(|name| resolve(index, name))
+            .collect::<ApiResult<FxHashSet<_>>>()?,
+        constellations: parse_id_list("avoid_constellations", &params.avoid_constellations)?,
+        regions: parse_id_list("avoid_regions", &params.avoid_regions)?,
+        security_classes: split_list(&params.avoid_security_classes)
+            .map(str::to_lowercase)
+            .collect(),
+        factions: parse_id_list("avoid_factions", &params.avoid_factions)?,
+    };
+
+    Ok(RouteOptions {
+        mode,
+        max_jump_range,
+        optimize: params.optimize.unwrap_or_default(),
+        weights,
+        constraints,
+    })
+}
+
+fn edge_weight(name: &str, value: Option<f64>, default: f64) -> ApiResult<f64> {
+    match value {
+        Some(weight) if !(weight.is_finite() && weight >= 0.0) => Err(ApiError::InvalidInput(
+            format!("{} must be a non-negative number", name),
+        )),
+        Some(weight) => Ok(weight),
+        None => Ok(default),
+    }
+}
+
+fn describe_mode(options: &RouteOptions) -> String {
+    let range_ly = options
+        .max_jump_range
+        .map(|range| Distance::from_meters(range).to_ly())
+        .unwrap_or(0.0);
+
+    match options.mode {
+        RouteMode::Gate => "stargate route".to_string(),
+        RouteMode::Jump => format!("jump route within {:.2} ly", range_ly),
+        RouteMode::Mixed => format!("mixed gate and jump route within {:.2} ly", range_ly),
+    }
+}
+
+fn resolve(index: &SpatialIndex, name_or_id: &str) -> ApiResult<SystemId> {
+    index
+        .resolve_system(name_or_id)
+        .ok_or_else(|| ApiError::SystemNotFound(name_or_id.to_string()))
+}
+
+/// Expand a route plan into hops with per-jump and cumulative distances
+fn build_route_response(index: &SpatialIndex, plan: &RoutePlan) -> ApiResult<RouteResponse> {
+    let mut route = Vec::with_capacity(plan.systems.len());
+    let mut cumulative_distance_ly = 0.0;
+    let mut previous: Option<Coordinate3D> = None;
+
+    for (i, &system_id) in plan.systems.iter().enumerate() {
+        let system = index.system_info(system_id, None).ok_or_else(|| {
+            ApiError::InternalError(anyhow::anyhow!(
+                "System {} is on a route but not in the index",
+                system_id
+            ))
+        })?;
+
+        let position = Coordinate3D::from_meters_array(system.center);
+        let jump_distance_ly = previous
+            .map(|prev| prev.distance_to(&position).to_ly())
+            .unwrap_or(0.0);
+        cumulative_distance_ly += jump_distance_ly;
+        previous = Some(position);
+
+        route.push(RouteHop {
+            system,
+            via: i.checked_sub(1).and_then(|link| plan.links.get(link).copied()),
+            jump_distance_ly,
+            cumulative_distance_ly,
+        });
+    }
+
+    Ok(RouteResponse {
+        jumps: route.len().saturating_sub(1),
+        total_distance_ly: cumulative_distance_ly,
+        total_cost: plan.cost,
+        route,
+    })
+}
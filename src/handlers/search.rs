@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    models::{SearchHit, SearchQuery, SearchResponse, SuggestionKind},
+    spatial::names::{NameMatch, MAX_QUERY_CHARS},
+    AppState,
+};
+
+/// Search systems, constellations, regions and type names at once
+#[utoipa::path(
+    get,
+    path = "/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Typed hits of every requested kind, ranked together", body = SearchResponse),
+        (status = 400, description = "Empty or overlong query or unknown kind"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "search"
+)]
+pub async fn search(
+    Query(params): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<SearchResponse>> {
+    if params.q.trim().is_empty() {
+        return Err(ApiError::InvalidInput("Query parameter 'q' cannot be empty".to_string()));
+    }
+    if params.q.chars().count() > MAX_QUERY_CHARS {
+        return Err(ApiError::InvalidInput(format!(
+            "Query parameter 'q' may be at most {} characters",
+            MAX_QUERY_CHARS
+        )));
+    }
+
+    let limit = params.limit.unwrap_or(20).min(100); // Cap at 100 results
+    let kinds = parse_kinds(params.kinds.as_deref())?;
+
+    info!("Searching for '{}' across {:?} (limit: {})", params.q, kinds, limit);
+
+    let index = &state.spatial_index;
+    let mut hits: Vec<SearchHit> = Vec::new();
+    for &kind in &kinds {
+        let matches = match kind {
+            SuggestionKind::System => index.autocomplete_systems(&params.q, limit, None, None),
+            SuggestionKind::Constellation => index.autocomplete_constellations(&params.q, limit, None, None),
+            SuggestionKind::Region => index.autocomplete_regions(&params.q, limit, None, None),
+            SuggestionKind::TypeName => state.type_names.search(&params.q, limit, |_| true),
+        };
+        hits.extend(matches.into_iter().map(|found: NameMatch| SearchHit {
+            kind,
+            id: found.id,
+            name: found.name,
+            score: found.score,
+        }));
+    }
+
+    // Every kind is scored the same way, so hits rank on one scale
+    hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then(a.id.cmp(&b.id))
+    });
+    hits.truncate(limit);
+
+    Ok(Json(SearchResponse {
+        hits,
+        query: params.q,
+    }))
+}
+
+fn parse_kinds(value: Option<&str>) -> ApiResult<Vec<SuggestionKind>> {
+    let Some(value) = value else {
+        return Ok(vec![
+            SuggestionKind::System,
+            SuggestionKind::Constellation,
+            SuggestionKind::Region,
+            SuggestionKind::TypeName,
+        ]);
+    };
+
+    let mut kinds = Vec::new();
+    for item in value.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        let kind = match item {
+            "system" => SuggestionKind::System,
+            "constellation" => SuggestionKind::Constellation,
+            "region" => SuggestionKind::Region,
+            "type_name" => SuggestionKind::TypeName,
+            other => {
+                return Err(ApiError::InvalidInput(format!(
+                    "Unknown kind '{}'; expected system, constellation, region or type_name",
+                    other
+                )));
+            }
+        };
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    if kinds.is_empty() {
+        return Err(ApiError::InvalidInput("kinds must list at least one kind".to_string()));
+    }
+    Ok(kinds)
+}
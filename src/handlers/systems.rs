@@ -8,24 +8,93 @@ use crate::{
     error::{ApiError, ApiResult},
     middleware::RequestId,
     models::{
-        NearbyQuery, NearestQuery, AutocompleteQuery, SystemLookupQuery, BulkSystemsQuery,
-        SystemHierarchyQuery, BulkConnectionsQuery,
+        CenterQuery, NearbyQuery, NearestQuery, AutocompleteQuery, SystemLookupQuery, BulkSystemsQuery,
+        SystemHierarchyQuery, BulkConnectionsQuery, DistanceMatrixRequest, DistanceMatrixResponse,
         NearbySystemsResponse, NearestSystemsResponse, AutocompleteResponse, BulkSystemsResponse,
         SystemInfo, SystemSuggestion, SystemMapData, SystemHierarchy, BulkConnectionsResponse,
-        CompleteSystemHierarchy,
+        CompleteSystemHierarchy, SuggestionKind, TerritorySuggestion, SystemSearchQuery,
+        SystemSearchResponse,
     },
-    coordinates::Distance,
+    coordinates::{Coordinate3D, Distance},
+    handlers::{parse_id_list, run_blocking, split_list, volumes::point_in_meters},
+    spatial::{filters::SystemFilter, names::MAX_QUERY_CHARS, routing::RouteOptions, Point3D, SpatialIndex, SystemId},
     AppState,
 };
 
+/// Most systems accepted by the distance matrix
+const MAX_MATRIX_SYSTEMS: usize = 200;
+
+/// Where a spatial query is centered
+struct QueryCenter {
+    position: Point3D,
+    /// Set when centered on a system, which is then left out of the results
+    system_id: Option<SystemId>,
+    /// Human-readable description for logging
+    label: String,
+}
+
+/// Resolve the center of a spatial query from exactly one of a name, an ID or a point
+fn resolve_query_center(
+    index: &SpatialIndex,
+    name: Option<&str>,
+    center: &CenterQuery,
+) -> ApiResult<QueryCenter> {
+    let point = match (center.x, center.y, center.z) {
+        (None, None, None) => None,
+        (Some(x), Some(y), Some(z)) => Some(point_in_meters(x, y, z, center.unit)?),
+        _ => {
+            return Err(ApiError::InvalidInput(
+                "x, y and z must all be given together".to_string(),
+            ));
+        }
+    };
+
+    let given = [name.is_some(), center.id.is_some(), point.is_some()]
+        .into_iter()
+        .filter(|given| *given)
+        .count();
+    if given != 1 {
+        return Err(ApiError::InvalidInput(
+            "Give exactly one of name, id or x,y,z as the search center".to_string(),
+        ));
+    }
+
+    if let Some(position) = point {
+        return Ok(QueryCenter {
+            position,
+            system_id: None,
+            label: Coordinate3D::from_meters_array(position).to_string(),
+        });
+    }
+
+    let system_id = match (name, center.id) {
+        (Some(name), _) => index
+            .find_system_by_name(name)
+            .ok_or_else(|| ApiError::SystemNotFound(name.to_string()))?,
+        (None, Some(id)) => id,
+        (None, None) => unreachable!("checked above"),
+    };
+    let system = index
+        .get_system(system_id)
+        .ok_or_else(|| ApiError::SystemNotFound(system_id.to_string()))?;
+
+    Ok(QueryCenter {
+        position: system.center,
+        system_id: Some(system_id),
+        label: name.map(str::to_string).unwrap_or_else(|| system_id.to_string()),
+    })
+}
+
 #[utoipa::path(
     get,
     path = "/systems/near",
     params(
-        NearbyQuery
+        NearbyQuery,
+        CenterQuery
     ),
     responses(
-        (status = 200, description = "Systems near the specified system (distances in light-years)", body = NearbySystemsResponse),
+        (status = 200, description = "Systems near the specified system or point (distances in light-years)", body = NearbySystemsResponse),
+        (status = 400, description = "Invalid search center"),
         (status = 404, description = "System not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -33,62 +102,36 @@ use crate::{
 )]
 pub async fn systems_near(
     Query(params): Query<NearbyQuery>,
+    Query(center): Query<CenterQuery>,
     State(state): State<AppState>,
     request_id: Option<RequestId>,
 ) -> ApiResult<Json<NearbySystemsResponse>> {
+    let query_center = resolve_query_center(&state.spatial_index, params.name.as_deref(), &center)?;
+
     // Log with request ID if available
     if let Some(RequestId(id)) = &request_id {
-        info!(request_id = %id, "Finding systems near '{}' within radius {:.2} ly", params.name, params.radius);
+        info!(request_id = %id, "Finding systems near {} within radius {:.2} ly", query_center.label, params.radius);
     } else {
-        info!("Finding systems near '{}' within radius {:.2} ly", params.name, params.radius);
+        info!("Finding systems near {} within radius {:.2} ly", query_center.label, params.radius);
     }
 
-    // Find the center system by name
-    let center_system_id = state
-        .spatial_index
-        .find_system_by_name(&params.name)
-        .ok_or_else(|| ApiError::SystemNotFound(params.name.clone()))?;
-
-    let center_system_data = state
-        .spatial_index
-        .get_system(center_system_id)
-        .ok_or_else(|| ApiError::InternalError(
-            anyhow::anyhow!("System {} exists in name index but not in data", center_system_id)
-        ))?;
-
     // Find nearby systems - convert radius from light-years to meters for spatial search
     let radius_meters = Distance::from_light_years(params.radius).to_meters();
     let nearby = state
         .spatial_index
-        .find_systems_within_radius(center_system_data.center, radius_meters);
-
-    let center_system = SystemInfo {
-        id: center_system_id,
-        name: Some(params.name.clone()),
-        center: center_system_data.center,
-        region_id: center_system_data.region_id,
-        constellation_id: center_system_data.constellation_id,
-        faction_id: center_system_data.metadata.faction_id,
-        distance: Some(0.0),
-    };
+        .find_systems_within_radius(query_center.position, radius_meters);
+
+    let center_system = query_center
+        .system_id
+        .and_then(|id| state.spatial_index.system_info(id, Some(0.0)));
 
     let nearby_systems: Vec<SystemInfo> = nearby
         .into_iter()
-        .filter(|(id, _)| *id != center_system_id) // Exclude the center system itself
+        .filter(|(id, _)| Some(*id) != query_center.system_id) // Exclude the center system itself
         .filter_map(|(id, distance_meters)| {
-            state.spatial_index.get_system(id).map(|sys| {
-                // Convert distance from meters to light-years for the response
-                let distance_ly = Distance::from_meters(distance_meters).to_ly();
-                SystemInfo {
-                    id,
-                    name: state.spatial_index.get_system_name(id).cloned(),
-                    center: sys.center,
-                    region_id: sys.region_id,
-                    constellation_id: sys.constellation_id,
-                    faction_id: sys.metadata.faction_id,
-                    distance: Some(distance_ly),
-                }
-            })
+            // Convert distance from meters to light-years for the response
+            let distance_ly = Distance::from_meters(distance_meters).to_ly();
+            state.spatial_index.system_info(id, Some(distance_ly))
         })
         .collect();
 
@@ -96,6 +139,7 @@ pub async fn systems_near(
 
     Ok(Json(NearbySystemsResponse {
         center_system,
+        center: query_center.position,
         nearby_systems,
         radius: params.radius,
         total_found,
@@ -106,10 +150,12 @@ pub async fn systems_near(
     get,
     path = "/systems/nearest",
     params(
-        NearestQuery
+        NearestQuery,
+        CenterQuery
     ),
     responses(
-        (status = 200, description = "Nearest systems to the specified system (distances in light-years)", body = NearestSystemsResponse),
+        (status = 200, description = "Nearest systems to the specified system or point (distances in light-years)", body = NearestSystemsResponse),
+        (status = 400, description = "Invalid search center"),
         (status = 404, description = "System not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -117,61 +163,36 @@ pub async fn systems_near(
 )]
 pub async fn systems_nearest(
     Query(params): Query<NearestQuery>,
+    Query(center): Query<CenterQuery>,
     State(state): State<AppState>,
 ) -> ApiResult<Json<NearestSystemsResponse>> {
-    info!("Finding {} nearest systems to '{}' (distances in ly)", params.k, params.name);
-
-    // Find the center system by name
-    let center_system_id = state
-        .spatial_index
-        .find_system_by_name(&params.name)
-        .ok_or_else(|| ApiError::SystemNotFound(params.name.clone()))?;
+    let query_center = resolve_query_center(&state.spatial_index, params.name.as_deref(), &center)?;
 
-    let center_system_data = state
-        .spatial_index
-        .get_system(center_system_id)
-        .ok_or_else(|| ApiError::InternalError(
-            anyhow::anyhow!("System {} exists in name index but not in data", center_system_id)
-        ))?;
+    info!("Finding {} nearest systems to {} (distances in ly)", params.k, query_center.label);
 
     // Find nearest systems (k+1 to account for the center system itself)
     let nearest = state
         .spatial_index
-        .find_nearest_systems(center_system_data.center, params.k + 1);
-
-    let center_system = SystemInfo {
-        id: center_system_id,
-        name: Some(params.name.clone()),
-        center: center_system_data.center,
-        region_id: center_system_data.region_id,
-        constellation_id: center_system_data.constellation_id,
-        faction_id: center_system_data.metadata.faction_id,
-        distance: Some(0.0),
-    };
+        .find_nearest_systems(query_center.position, params.k + 1);
+
+    let center_system = query_center
+        .system_id
+        .and_then(|id| state.spatial_index.system_info(id, Some(0.0)));
 
     let nearest_systems: Vec<SystemInfo> = nearest
         .into_iter()
-        .filter(|(id, _)| *id != center_system_id) // Exclude the center system itself
+        .filter(|(id, _)| Some(*id) != query_center.system_id) // Exclude the center system itself
         .take(params.k) // Take only k systems
         .filter_map(|(id, distance_meters)| {
-            state.spatial_index.get_system(id).map(|sys| {
-                // Convert distance from meters to light-years for the response
-                let distance_ly = Distance::from_meters(distance_meters).to_ly();
-                SystemInfo {
-                    id,
-                    name: state.spatial_index.get_system_name(id).cloned(),
-                    center: sys.center,
-                    region_id: sys.region_id,
-                    constellation_id: sys.constellation_id,
-                    faction_id: sys.metadata.faction_id,
-                    distance: Some(distance_ly),
-                }
-            })
+            // Convert distance from meters to light-years for the response
+            let distance_ly = Distance::from_meters(distance_meters).to_ly();
+            state.spatial_index.system_info(id, Some(distance_ly))
         })
         .collect();
 
     Ok(Json(NearestSystemsResponse {
         center_system,
+        center: query_center.position,
         nearest_systems,
         k: params.k,
     }))
@@ -184,7 +205,9 @@ pub async fn systems_nearest(
         AutocompleteQuery
     ),
     responses(
-        (status = 200, description = "System name suggestions", body = AutocompleteResponse),
+        (status = 200, description = "System, constellation and region name suggestions, best match first", body = AutocompleteResponse),
+        (status = 400, description = "Query too long"),
+        (status = 404, description = "Region or constellation filter not found"),
         (status = 500, description = "Internal server error")
     ),
     tag = "systems"
@@ -193,24 +216,87 @@ pub async fn systems_autocomplete(
     Query(params): Query<AutocompleteQuery>,
     State(state): State<AppState>,
 ) -> ApiResult<Json<AutocompleteResponse>> {
+    let index = &state.spatial_index;
     let limit = params.limit.unwrap_or(10).min(50); // Cap at 50 results
-    
-    info!("Autocomplete search for '{}' (limit: {})", params.q, limit);
+    let (region_id, constellation_id) = (params.region_id, params.constellation_id);
 
-    let suggestions: Vec<SystemSuggestion> = state
-        .spatial_index
-        .autocomplete_systems(&params.q, limit)
+    if params.q.chars().count() > MAX_QUERY_CHARS {
+        return Err(ApiError::InvalidInput(format!(
+            "Query parameter 'q' may be at most {} characters",
+            MAX_QUERY_CHARS
+        )));
+    }
+
+    if let Some(region_id) = region_id {
+        index.get_region(region_id).ok_or(ApiError::RegionNotFound(region_id))?;
+    }
+    if let Some(constellation_id) = constellation_id {
+        index
+            .get_constellation(constellation_id)
+            .ok_or(ApiError::ConstellationNotFound(constellation_id))?;
+    }
+
+    info!(
+        "Autocomplete search for '{}' (limit: {}, region: {:?}, constellation: {:?})",
+        params.q, limit, region_id, constellation_id
+    );
+
+    let region_name = |id: Option<u32>| id.and_then(|id| index.get_region_name(id)).cloned();
+
+    let suggestions: Vec<SystemSuggestion> = index
+        .autocomplete_systems(&params.q, limit, region_id, constellation_id)
+        .into_iter()
+        .filter_map(|found| {
+            let system = index.get_system(found.id)?;
+            Some(SystemSuggestion {
+                kind: SuggestionKind::System,
+                id: found.id,
+                name: found.name,
+                score: found.score,
+                region_id: system.region_id,
+                region_name: region_name(system.region_id),
+                constellation_id: system.constellation_id,
+                constellation_name: system
+                    .constellation_id
+                    .and_then(|id| index.get_constellation_name(id))
+                    .cloned(),
+            })
+        })
+        .collect();
+
+    let constellations = index
+        .autocomplete_constellations(&params.q, limit, region_id, constellation_id)
+        .into_iter()
+        .map(|found| {
+            let parent = index.get_constellation(found.id).map(|c| c.region_id);
+            TerritorySuggestion {
+                kind: SuggestionKind::Constellation,
+                id: found.id,
+                name: found.name,
+                score: found.score,
+                region_id: parent,
+                region_name: region_name(parent),
+            }
+        })
+        .collect();
+
+    let regions = index
+        .autocomplete_regions(&params.q, limit, region_id, constellation_id)
         .into_iter()
-        .map(|(name, id)| SystemSuggestion {
-            id,
-            name,
-            region_name: None,     // TODO: Lookup region names
-            constellation_name: None, // TODO: Lookup constellation names
+        .map(|found| TerritorySuggestion {
+            kind: SuggestionKind::Region,
+            id: found.id,
+            name: found.name,
+            score: found.score,
+            region_id: None,
+            region_name: None,
         })
         .collect();
 
     Ok(Json(AutocompleteResponse {
         suggestions,
+        constellations,
+        regions,
         query: params.q,
     }))
 }
@@ -234,25 +320,12 @@ pub async fn systems_lookup(
 ) -> ApiResult<Json<SystemInfo>> {
     info!("Looking up system with ID: {}", params.id);
 
-    // Get system data
-    let system_data = state
+    // No distance calculation for direct lookup
+    let system_info = state
         .spatial_index
-        .get_system(params.id)
+        .system_info(params.id, None)
         .ok_or_else(|| ApiError::SystemNotFound(params.id.to_string()))?;
 
-    // Get system name
-    let system_name = state.spatial_index.get_system_name(params.id).cloned();
-
-    let system_info = SystemInfo {
-        id: params.id,
-        name: system_name,
-        center: system_data.center,
-        region_id: system_data.region_id,
-        constellation_id: system_data.constellation_id,
-        faction_id: system_data.metadata.faction_id,
-        distance: None, // No distance calculation for direct lookup
-    };
-
     Ok(Json(system_info))
 }
 
@@ -337,9 +410,9 @@ pub async fn system_hierarchy(
 
     let hierarchy = state
         .database
-        .get_system_hierarchy(params.id)
+        .get_system_hierarchy(&state.spatial_index, params.id)
         .await
-        .map_err(|e| ApiError::InternalError(e))?
+        .map_err(ApiError::InternalError)?
         .ok_or_else(|| ApiError::SystemNotFound(params.id.to_string()))?;
 
     Ok(Json(hierarchy))
@@ -366,9 +439,9 @@ pub async fn complete_system_hierarchy(
 
     let hierarchy = state
         .database
-        .get_complete_system_hierarchy(params.id)
+        .get_complete_system_hierarchy(&state.spatial_index, params.id)
         .await
-        .map_err(|e| ApiError::InternalError(e))?
+        .map_err(ApiError::InternalError)?
         .ok_or_else(|| ApiError::SystemNotFound(params.id.to_string()))?;
 
     Ok(Json(hierarchy))
@@ -402,7 +475,7 @@ pub async fn systems_connections_bulk(
         .database
         .get_all_connections(limit, offset, params.connection_type.as_deref())
         .await
-        .map_err(|e| ApiError::InternalError(e))?;
+        .map_err(ApiError::InternalError)?;
 
     Ok(Json(BulkConnectionsResponse {
         connections,
@@ -410,4 +483,175 @@ pub async fn systems_connections_bulk(
         offset,
         limit,
     }))
-} 
\ No newline at end of file
+} 
+
+/// Pairwise distances (and optionally gate jumps) between a set of systems
+#[utoipa::path(
+    post,
+    path = "/systems/distance-matrix",
+    request_body = DistanceMatrixRequest,
+    responses(
+        (status = 200, description = "Pairwise distance matrix in light-years", body = DistanceMatrixResponse),
+        (status = 400, description = "Too many or no systems"),
+        (status = 404, description = "System not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "systems"
+)]
+pub async fn systems_distance_matrix(
+    State(state): State<AppState>,
+    Json(request): Json<DistanceMatrixRequest>,
+) -> ApiResult<Json<DistanceMatrixResponse>> {
+    run_blocking(&state.spatial_index, move |index| {
+        if request.systems.is_empty() || request.systems.len() > MAX_MATRIX_SYSTEMS {
+            return Err(ApiError::InvalidInput(format!(
+                "Give between 1 and {} systems",
+                MAX_MATRIX_SYSTEMS
+            )));
+        }
+        let include_hops = request.include_hops.unwrap_or(false);
+        info!(
+            "Distance matrix request: {} systems (hops: {})",
+            request.systems.len(),
+            include_hops
+        );
+
+        let systems = request
+            .systems
+            .iter()
+            .map(|name_or_id| {
+                index
+                    .resolve_system(name_or_id)
+                    .and_then(|id| index.system_info(id, None))
+                    .ok_or_else(|| ApiError::SystemNotFound(name_or_id.clone()))
+            })
+            .collect::<ApiResult<Vec<SystemInfo>>>()?;
+
+        let positions: Vec<Coordinate3D> = systems
+            .iter()
+            .map(|system| Coordinate3D::from_meters_array(system.center))
+            .collect();
+        let distances = positions
+            .iter()
+            .map(|from| positions.iter().map(|to| from.distance_to(to).to_ly()).collect())
+            .collect();
+
+        let hops = include_hops.then(|| {
+            let ids: Vec<SystemId> = systems.iter().map(|system| system.id).collect();
+            let options = RouteOptions::default();
+            ids.iter()
+                .map(|&from| index.hop_counts(from, &ids, &options))
+                .collect()
+        });
+
+        Ok(DistanceMatrixResponse {
+            systems,
+            distances,
+            hops,
+        })
+    })
+    .await
+    .map(Json)
+}
+
+/// Find systems by planets, security, faction, sovereignty, star and anchoring rules, optionally within a radius
+#[utoipa::path(
+    get,
+    path = "/systems/search",
+    params(SystemSearchQuery, CenterQuery),
+    responses(
+        (status = 200, description = "One page of systems meeting every filter", body = SystemSearchResponse),
+        (status = 400, description = "Invalid filter or center"),
+        (status = 404, description = "Center system, region or constellation not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "systems"
+)]
+pub async fn systems_search(
+    Query(params): Query<SystemSearchQuery>,
+    Query(center): Query<CenterQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<SystemSearchResponse>> {
+    let index = &state.spatial_index;
+    let limit = params.limit.unwrap_or(100).min(1000); // Cap at 1000 results
+    let offset = params.offset.unwrap_or(0);
+
+    let min_planets = split_list(&params.planets)
+        .map(|pair| {
+            let invalid = || {
+                ApiError::InvalidInput(format!(
+                    "planets entries must look like planet_type_id:count, got '{}'",
+                    pair
+                ))
+            };
+            let (planet_type, count) = pair.split_once(':').ok_or_else(invalid)?;
+            let planet_type = planet_type.trim().parse::<u32>().map_err(|_| invalid())?;
+            let count = count.trim().parse::<u32>().map_err(|_| invalid())?;
+            Ok((planet_type.to_string(), count))
+        })
+        .collect::<ApiResult<Vec<_>>>()?;
+
+    for (name, value) in [("min_security", params.min_security), ("max_security", params.max_security)] {
+        if value.is_some_and(|value| !value.is_finite()) {
+            return Err(ApiError::InvalidInput(format!("{} must be a number", name)));
+        }
+    }
+    if let Some(region_id) = params.region_id {
+        index.get_region(region_id).ok_or(ApiError::RegionNotFound(region_id))?;
+    }
+    if let Some(constellation_id) = params.constellation_id {
+        index
+            .get_constellation(constellation_id)
+            .ok_or(ApiError::ConstellationNotFound(constellation_id))?;
+    }
+
+    let filter = SystemFilter {
+        min_planets,
+        security_classes: split_list(&params.security_class).map(str::to_lowercase).collect(),
+        min_security: params.min_security,
+        max_security: params.max_security,
+        factions: parse_id_list("faction_id", &params.faction_id)?,
+        sovereignties: split_list(&params.sovereignty).map(str::to_lowercase).collect(),
+        has_star: params.has_star,
+        anchor_categories: split_list(&params.allows_anchor_category).map(str::to_string).collect(),
+        anchor_groups: split_list(&params.allows_anchor_group).map(str::to_string).collect(),
+        region_id: params.region_id,
+        constellation_id: params.constellation_id,
+    };
+
+    let has_center = params.name.is_some()
+        || center.id.is_some()
+        || [center.x, center.y, center.z].iter().any(Option::is_some);
+    let within = match params.radius {
+        Some(radius) if !(radius.is_finite() && radius >= 0.0) => {
+            return Err(ApiError::InvalidInput("radius must be a non-negative number".to_string()));
+        }
+        Some(radius) => {
+            let query_center = resolve_query_center(index, params.name.as_deref(), &center)?;
+            info!("Searching systems within {:.2} ly of {}", radius, query_center.label);
+            Some((query_center.position, Distance::from_light_years(radius).to_meters()))
+        }
+        None if has_center => {
+            return Err(ApiError::InvalidInput("radius is required when a center is given".to_string()));
+        }
+        None => None,
+    };
+
+    info!("Searching systems by attributes: {:?} (limit: {}, offset: {})", filter, limit, offset);
+
+    let found = index.filter_systems(&filter, within);
+    let total_matches = found.len();
+    let systems = found
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|(id, distance)| index.system_info(id, distance.map(|d| Distance::from_meters(d).to_ly())))
+        .collect();
+
+    Ok(Json(SystemSearchResponse {
+        systems,
+        total_matches,
+        offset,
+        limit,
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,293 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    coordinates::{Coordinate3D, Distance},
+    error::{ApiError, ApiResult},
+    models::{
+        BoxQuery, CoordinateUnit, CorridorQuery, CorridorResponse, CorridorSystem, FrustumQuery,
+        VolumeQueryResponse,
+    },
+    spatial::{
+        volume::{rotation_from_quaternion, Corridor, Frustum, OrientedBox, Rotation, IDENTITY},
+        Point3D, SpatialIndex, SystemId,
+    },
+    AppState,
+};
+
+/// Convert a query point to meters, rejecting non-finite values
+pub(crate) fn point_in_meters(
+    x: f64,
+    y: f64,
+    z: f64,
+    unit: Option<CoordinateUnit>,
+) -> ApiResult<Point3D> {
+    if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+        return Err(ApiError::InvalidInput(
+            "Coordinates must be finite numbers".to_string(),
+        ));
+    }
+    let point = match unit.unwrap_or_default() {
+        CoordinateUnit::Meters => Coordinate3D::from_meters(x, y, z),
+        CoordinateUnit::Ly => Coordinate3D::from_light_years(x, y, z),
+    };
+    Ok(point.to_meters_array())
+}
+
+/// Convert a query length to meters
+fn length_in_meters(value: f64, unit: Option<CoordinateUnit>) -> f64 {
+    match unit.unwrap_or_default() {
+        CoordinateUnit::Meters => value,
+        CoordinateUnit::Ly => Distance::from_light_years(value).to_meters(),
+    }
+}
+
+/// Rotation from optional quaternion components: none for no rotation, all four otherwise
+fn optional_rotation(
+    qx: Option<f64>,
+    qy: Option<f64>,
+    qz: Option<f64>,
+    qw: Option<f64>,
+) -> ApiResult<Option<Rotation>> {
+    match (qx, qy, qz, qw) {
+        (None, None, None, None) => Ok(None),
+        (Some(x), Some(y), Some(z), Some(w)) => rotation_from_quaternion(x, y, z, w)
+            .map(Some)
+            .ok_or_else(|| {
+                ApiError::InvalidInput("Rotation quaternion must be finite and non-zero".to_string())
+            }),
+        _ => Err(ApiError::InvalidInput(
+            "Give all of qx, qy, qz and qw, or none of them".to_string(),
+        )),
+    }
+}
+
+/// Resolve one end of a corridor from either a system name or ID, or a point
+fn corridor_end(
+    index: &SpatialIndex,
+    end: &str,
+    system: Option<&str>,
+    point: [Option<f64>; 3],
+    unit: Option<CoordinateUnit>,
+) -> ApiResult<Point3D> {
+    match (system, point) {
+        (Some(name_or_id), [None, None, None]) => index
+            .resolve_system(name_or_id)
+            .and_then(|id| index.get_system(id))
+            .map(|system| system.center)
+            .ok_or_else(|| ApiError::SystemNotFound(name_or_id.to_string())),
+        (None, [Some(x), Some(y), Some(z)]) => point_in_meters(x, y, z, unit),
+        _ => Err(ApiError::InvalidInput(format!(
+            "Give either {end} or all of {end}_x, {end}_y and {end}_z"
+        ))),
+    }
+}
+
+/// Page through matching systems and build the response
+fn volume_response(
+    index: &SpatialIndex,
+    found: Vec<(SystemId, Option<f64>)>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> VolumeQueryResponse {
+    let limit = limit.unwrap_or(1000).min(5000); // Cap at 5000 systems
+    let offset = offset.unwrap_or(0);
+    let total_found = found.len();
+
+    let systems = found
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|(id, distance)| index.system_info(id, distance))
+        .collect();
+
+    VolumeQueryResponse {
+        systems,
+        total_found,
+        offset,
+        limit,
+    }
+}
+
+/// Find all systems inside an axis-aligned or rotated box
+#[utoipa::path(
+    get,
+    path = "/systems/in-box",
+    params(BoxQuery),
+    responses(
+        (status = 200, description = "Systems inside the box, sorted by ID", body = VolumeQueryResponse),
+        (status = 400, description = "Invalid box or rotation"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "systems"
+)]
+pub async fn systems_in_box(
+    Query(params): Query<BoxQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<VolumeQueryResponse>> {
+    let min = point_in_meters(params.min_x, params.min_y, params.min_z, params.unit)?;
+    let max = point_in_meters(params.max_x, params.max_y, params.max_z, params.unit)?;
+    let rotation = optional_rotation(params.qx, params.qy, params.qz, params.qw)?;
+
+    info!(
+        "Finding systems in box {:?} - {:?} (rotated: {})",
+        min,
+        max,
+        rotation.is_some()
+    );
+
+    let mut volume = OrientedBox::from_corners(min, max);
+    if let Some(rotation) = rotation {
+        volume = volume.rotated(rotation);
+    }
+
+    let found = state
+        .spatial_index
+        .find_systems_in_box(&volume)
+        .into_iter()
+        .map(|id| (id, None))
+        .collect();
+
+    Ok(Json(volume_response(&state.spatial_index, found, params.limit, params.offset)))
+}
+
+/// Find the systems visible to a perspective camera
+#[utoipa::path(
+    get,
+    path = "/systems/in-frustum",
+    params(FrustumQuery),
+    responses(
+        (status = 200, description = "Systems inside the view frustum, nearest the camera first", body = VolumeQueryResponse),
+        (status = 400, description = "Invalid camera parameters"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "systems"
+)]
+pub async fn systems_in_frustum(
+    Query(params): Query<FrustumQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<VolumeQueryResponse>> {
+    let position = point_in_meters(params.x, params.y, params.z, params.unit)?;
+    let rotation = optional_rotation(params.qx, params.qy, params.qz, params.qw)?.unwrap_or(IDENTITY);
+    let near = length_in_meters(params.near, params.unit);
+    let far = length_in_meters(params.far, params.unit);
+    let aspect = params.aspect.unwrap_or(1.0);
+
+    if !(params.fov > 0.0 && params.fov < 180.0) {
+        return Err(ApiError::InvalidInput(
+            "fov must be between 0 and 180 degrees".to_string(),
+        ));
+    }
+    if !(aspect.is_finite() && aspect > 0.0) {
+        return Err(ApiError::InvalidInput("aspect must be positive".to_string()));
+    }
+    if !(near >= 0.0 && far > near && far.is_finite()) {
+        return Err(ApiError::InvalidInput(
+            "near must be non-negative and less than far".to_string(),
+        ));
+    }
+
+    info!(
+        "Finding systems in frustum from {:?} (fov {}, near {}, far {})",
+        position, params.fov, params.near, params.far
+    );
+
+    let frustum = Frustum {
+        position,
+        rotation,
+        fov_y: params.fov.to_radians(),
+        aspect,
+        near,
+        far,
+    };
+
+    let found = state
+        .spatial_index
+        .find_systems_in_frustum(&frustum)
+        .into_iter()
+        .map(|(id, distance)| (id, Some(Distance::from_meters(distance).to_ly())))
+        .collect();
+
+    Ok(Json(volume_response(&state.spatial_index, found, params.limit, params.offset)))
+}
+
+/// Find all systems within a radius of the segment between two systems or points
+#[utoipa::path(
+    get,
+    path = "/systems/corridor",
+    params(CorridorQuery),
+    responses(
+        (status = 200, description = "Systems along the corridor, ordered by their projection onto the segment", body = CorridorResponse),
+        (status = 400, description = "Invalid corridor"),
+        (status = 404, description = "System not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "systems"
+)]
+pub async fn systems_corridor(
+    Query(params): Query<CorridorQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<CorridorResponse>> {
+    let index = &state.spatial_index;
+    let start = corridor_end(
+        index,
+        "from",
+        params.from.as_deref(),
+        [params.from_x, params.from_y, params.from_z],
+        params.unit,
+    )?;
+    let end = corridor_end(
+        index,
+        "to",
+        params.to.as_deref(),
+        [params.to_x, params.to_y, params.to_z],
+        params.unit,
+    )?;
+    if !(params.radius.is_finite() && params.radius >= 0.0) {
+        return Err(ApiError::InvalidInput("radius must be non-negative".to_string()));
+    }
+
+    let corridor = Corridor {
+        start,
+        end,
+        radius: Distance::from_light_years(params.radius).to_meters(),
+    };
+    let length = Distance::from_meters(corridor.length()).to_ly();
+
+    info!(
+        "Finding systems within {} ly of a {:.2} ly corridor",
+        params.radius, length
+    );
+
+    let hits = index.find_systems_in_corridor(&corridor);
+    let limit = params.limit.unwrap_or(1000).min(5000); // Cap at 5000 systems
+    let offset = params.offset.unwrap_or(0);
+    let total_found = hits.len();
+
+    let systems = hits
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|hit| {
+            let system = index.system_info(hit.system_id, Some(Distance::from_meters(hit.offset).to_ly()))?;
+            Some(CorridorSystem {
+                system,
+                along: Distance::from_meters(hit.along).to_ly(),
+            })
+        })
+        .collect();
+
+    Ok(Json(CorridorResponse {
+        from: start,
+        to: end,
+        length,
+        radius: params.radius,
+        systems,
+        total_found,
+        offset,
+        limit,
+    }))
+}
@@ -1,15 +1,13 @@
 use axum::{
-    routing::get,
+    routing::{get, post},
     Router,
     middleware as axum_middleware,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tracing::{info, Level};
-use tracing_subscriber;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
-use serde_json;
 
 mod spatial;
 mod models;
@@ -19,8 +17,8 @@ mod error;
 mod middleware;
 pub mod coordinates;
 
-use handlers::{health, systems, type_names};
-use spatial::SpatialIndex;
+use handlers::{density, health, map, network, regions, routing, search, systems, type_names, volumes};
+use spatial::{names::NameIndex, SpatialIndex};
 use database::Database;
 
 #[derive(OpenApi)]
@@ -35,10 +33,38 @@ use database::Database;
         systems::system_hierarchy,
         systems::complete_system_hierarchy,
         systems::systems_connections_bulk,
+        systems::systems_distance_matrix,
+        systems::systems_search,
+        volumes::systems_in_box,
+        volumes::systems_in_frustum,
+        volumes::systems_corridor,
+        density::systems_clusters,
+
+        // Routing endpoints
+        routing::systems_route,
+        routing::systems_route_alternatives,
+        routing::systems_route_tour,
+        routing::systems_rendezvous,
+        routing::systems_reachable,
+
+        // Network analysis endpoints
+        network::network_chokepoints,
+        network::systems_hubs,
+        network::network_components,
         
+        // Region endpoints
+        regions::region_details,
+        regions::constellation_details,
+        regions::region_layout,
+        regions::locate_territory,
+
+        // Map endpoints
+        map::map_tile,
+
         // Type names endpoints
         type_names::search_type_names,
         type_names::get_type_name,
+        search::search,
         
         // Health endpoint
         health::health_check,
@@ -52,7 +78,25 @@ use database::Database;
             models::BulkSystemsResponse,
             models::SystemInfo,
             models::SystemSuggestion,
+            models::SuggestionKind,
+            models::TerritorySuggestion,
             models::SystemMapData,
+            models::MapTileResponse,
+            models::RegionDetails,
+            models::ConstellationDetails,
+            models::GeometryInfo,
+            models::HullInfo,
+            models::HullVertex,
+            models::TerritoryMatchInfo,
+            models::TerritoryLookupResponse,
+            models::LayoutPosition,
+            models::RegionLayoutResponse,
+            models::VolumeQueryResponse,
+            models::CorridorSystem,
+            models::CorridorResponse,
+            models::SystemCluster,
+            models::ClusteredSystem,
+            models::ClustersResponse,
             models::SystemHierarchy,
             models::RegionInfo,
             models::ConstellationInfo,
@@ -62,20 +106,67 @@ use database::Database;
             models::GateConnection,
             models::SystemConnections,
             models::BulkConnectionsResponse,
+            models::DistanceMatrixRequest,
+            models::DistanceMatrixResponse,
+            models::SystemSearchQuery,
+            models::SystemSearchResponse,
+            models::RouteHop,
+            models::RouteResponse,
+            models::ReachableSystem,
+            models::ReachableSystemsResponse,
+            models::AlternativeRoute,
+            models::AlternativeRoutesResponse,
+            models::GateBridge,
+            models::ChokepointsResponse,
+            models::HubsResponse,
+            models::JumpEntryPoint,
+            models::GateComponentInfo,
+            models::ComponentsResponse,
+            models::SystemCentrality,
+            models::TourResponse,
+            models::PilotCost,
+            models::RendezvousCandidate,
+            models::RendezvousResponse,
+            models::RendezvousObjective,
 
             // Type names models
             models::TypeName,
             models::TypeNameResponse,
+            models::SearchHit,
+            models::SearchResponse,
             
             // Query models
             models::NearbyQuery,
             models::NearestQuery,
+            models::CenterQuery,
+            models::CoordinateUnit,
+            models::BoxQuery,
+            models::FrustumQuery,
+            models::CorridorQuery,
+            models::ClustersQuery,
             models::AutocompleteQuery,
             models::SystemLookupQuery,
             models::BulkSystemsQuery,
             models::SystemHierarchyQuery,
             models::BulkConnectionsQuery,
+            models::RouteQuery,
+            models::RouteParams,
+            models::ReachableQuery,
+            models::ChokepointsQuery,
+            models::HubsQuery,
+            models::ComponentsQuery,
+            models::CentralityMetric,
+            models::LayoutQuery,
+            models::TerritoryLookupQuery,
+            models::LayoutMethod,
+            models::AlternativeRoutesQuery,
+            models::TourQuery,
+            models::RendezvousQuery,
+            models::RouteMode,
+            models::RouteOptimize,
+            models::RouteLink,
             models::TypeNameQuery,
+            models::SearchQuery,
             
             // Health response
             health::HealthResponse,
@@ -83,7 +174,12 @@ use database::Database;
     ),
     tags(
         (name = "systems", description = "Solar system spatial queries and search"),
+        (name = "routing", description = "Route planning across the gate network"),
+        (name = "network", description = "Structural analysis of the gate network"),
+        (name = "regions", description = "Region and constellation geometry and layouts"),
+        (name = "map", description = "Level-of-detail map tiles"),
         (name = "type-names", description = "EVE type ID to name lookup functionality"),
+        (name = "search", description = "Ranked search across systems, territories and type names"),
         (name = "health", description = "Service health monitoring")
     ),
     info(
@@ -121,6 +217,11 @@ async fn main() -> anyhow::Result<()> {
     let spatial_index = Arc::new(SpatialIndex::load_with_cache(&db, &data_dir, "data/cache/spatial_index.bin").await?);
     info!("Loaded {} systems into spatial index", spatial_index.system_count());
 
+    // Type names live in the database rather than the cached index
+    let type_names = db.load_type_names().await?;
+    info!("Indexed {} type names for search", type_names.len());
+    let type_names = Arc::new(NameIndex::build(type_names.into_iter().map(|t| (t.name, t.type_id))));
+
     // Get path prefix from environment variable
     let path_prefix = std::env::var("PATH_PREFIX").unwrap_or_default();
     info!("Using path prefix: '{}'", path_prefix);
@@ -139,12 +240,37 @@ async fn main() -> anyhow::Result<()> {
         .route(&format!("{}/systems/hierarchy", path_prefix), get(systems::system_hierarchy))
         .route(&format!("{}/systems/hierarchy/complete", path_prefix), get(systems::complete_system_hierarchy))
         .route(&format!("{}/systems/connections/bulk", path_prefix), get(systems::systems_connections_bulk))
+        .route(&format!("{}/systems/distance-matrix", path_prefix), post(systems::systems_distance_matrix))
+        .route(&format!("{}/systems/search", path_prefix), get(systems::systems_search))
+        .route(&format!("{}/systems/in-box", path_prefix), get(volumes::systems_in_box))
+        .route(&format!("{}/systems/in-frustum", path_prefix), get(volumes::systems_in_frustum))
+        .route(&format!("{}/systems/corridor", path_prefix), get(volumes::systems_corridor))
+        .route(&format!("{}/systems/clusters", path_prefix), get(density::systems_clusters))
+        // Routing
+        .route(&format!("{}/systems/route", path_prefix), get(routing::systems_route))
+        .route(&format!("{}/systems/route/alternatives", path_prefix), get(routing::systems_route_alternatives))
+        .route(&format!("{}/systems/route/tour", path_prefix), get(routing::systems_route_tour))
+        .route(&format!("{}/systems/rendezvous", path_prefix), get(routing::systems_rendezvous))
+        .route(&format!("{}/systems/reachable", path_prefix), get(routing::systems_reachable))
+        // Network analysis
+        .route(&format!("{}/network/chokepoints", path_prefix), get(network::network_chokepoints))
+        .route(&format!("{}/systems/hubs", path_prefix), get(network::systems_hubs))
+        .route(&format!("{}/network/components", path_prefix), get(network::network_components))
+        // Regions
+        .route(&format!("{}/regions/:id", path_prefix), get(regions::region_details))
+        .route(&format!("{}/regions/:id/layout", path_prefix), get(regions::region_layout))
+        .route(&format!("{}/constellations/:id", path_prefix), get(regions::constellation_details))
+        .route(&format!("{}/territories/locate", path_prefix), get(regions::locate_territory))
+        // Map tiles
+        .route(&format!("{}/map/tiles/:level/:x/:y/:z", path_prefix), get(map::map_tile))
         // Type names routes
         .route(&format!("{}/type-names/search", path_prefix), get(type_names::search_type_names))
         .route(&format!("{}/type-names/:type_id", path_prefix), get(type_names::get_type_name))
+        .route(&format!("{}/search", path_prefix), get(search::search))
         .with_state(AppState {
             database: db,
             spatial_index,
+            type_names,
         });
 
     // Apply individual middleware layers
@@ -177,4 +303,5 @@ struct AppState {
     #[allow(dead_code)]
     database: Database,
     spatial_index: Arc<SpatialIndex>,
+    type_names: Arc<NameIndex>,
 } 
\ No newline at end of file
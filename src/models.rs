@@ -90,6 +90,17 @@ pub struct SystemInfo {
     pub faction_id: Option<u32>,
     /// Distance from query center in light-years
     pub distance: Option<f64>,
+    /// Position of the system in the gate network (absent where the index is not consulted)
+    pub centrality: Option<SystemCentrality>,
+}
+
+/// How central a system is to the gate network
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SystemCentrality {
+    /// Share of shortest gate paths between other systems that pass through this one (0 to 1)
+    pub betweenness: f64,
+    /// Closeness to every other system by gate jumps, scaled by the share of the network reachable (0 to 1)
+    pub closeness: f64,
 }
 
 // New detailed models for hierarchical data
@@ -158,6 +169,225 @@ pub struct BulkConnectionsResponse {
     pub limit: usize,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RouteHop {
+    pub system: SystemInfo,
+    /// How this system was reached (absent for the origin)
+    pub via: Option<RouteLink>,
+    /// Straight-line length of the jump into this system in light-years (0 for the origin)
+    pub jump_distance_ly: f64,
+    /// Distance travelled from the origin up to and including this hop in light-years
+    pub cumulative_distance_ly: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RouteResponse {
+    /// Systems in travel order, starting with the origin and ending with the destination
+    pub route: Vec<RouteHop>,
+    /// Number of jumps (hops excluding the origin)
+    pub jumps: usize,
+    /// Total distance travelled in light-years
+    pub total_distance_ly: f64,
+    /// Total cost under the requested optimisation target and edge weights
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReachableSystem {
+    /// Reached system; `distance` is the straight-line distance from the origin in light-years
+    pub system: SystemInfo,
+    /// Fewest jumps needed to reach this system
+    pub hops: u32,
+    /// System this one is reached from on a fewest-jump path
+    pub previous_system_id: u32,
+    /// How the last jump is made
+    pub via: RouteLink,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReachableSystemsResponse {
+    pub origin: SystemInfo,
+    /// One page of the systems reachable within the jump budget, ordered by hop count (origin excluded)
+    pub reachable_systems: Vec<ReachableSystem>,
+    /// Total hop budget, counting gate jumps and jump drive hops alike
+    pub max_jumps: u32,
+    /// Jump drive range in light-years, if jump drive hops were allowed
+    pub max_jump_ly: Option<f64>,
+    /// Reachable systems across all pages
+    pub total_found: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AlternativeRoute {
+    /// 1 for the best route, 2 for the next cheapest and so on
+    pub rank: usize,
+    /// Systems in travel order, starting with the origin and ending with the destination
+    pub route: Vec<RouteHop>,
+    /// Number of jumps (hops excluding the origin)
+    pub jumps: usize,
+    /// Total distance travelled in light-years
+    pub total_distance_ly: f64,
+    /// Total cost under the requested optimisation target and edge weights
+    pub total_cost: f64,
+    /// Systems on this route that the best route does not pass through (empty for the best route)
+    pub differs_from_best: Vec<SystemInfo>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AlternativeRoutesResponse {
+    /// Loopless routes in increasing cost order, best first
+    pub routes: Vec<AlternativeRoute>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TourResponse {
+    /// Requested stops in the order they are visited
+    pub stops: Vec<SystemInfo>,
+    /// Full route through every stop, hop by hop
+    pub route: Vec<RouteHop>,
+    /// Number of jumps along the whole route
+    pub jumps: usize,
+    /// Total distance travelled in light-years
+    pub total_distance_ly: f64,
+    /// Total cost under the requested optimisation target and edge weights
+    pub total_cost: f64,
+    /// Always true: the visiting order is a heuristic (nearest-neighbour + 2-opt), not a proven optimum
+    pub approximate: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PilotCost {
+    /// Starting system, as given in the request
+    pub from_system_id: u32,
+    /// Route cost from the starting system under the requested optimisation target and edge weights
+    pub cost: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RendezvousCandidate {
+    /// Meeting system
+    pub system: SystemInfo,
+    /// Highest cost any single pilot pays to arrive
+    pub max_cost: f64,
+    /// Combined cost of every pilot's route
+    pub total_cost: f64,
+    /// Cost for each pilot, in request order
+    pub pilot_costs: Vec<PilotCost>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RendezvousResponse {
+    /// Starting systems in request order
+    pub starts: Vec<SystemInfo>,
+    pub objective: RendezvousObjective,
+    /// Best meeting systems first
+    pub candidates: Vec<RendezvousCandidate>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HubsResponse {
+    /// Region the ranking is restricted to, or absent for the whole network
+    pub region_id: Option<u32>,
+    pub metric: CentralityMetric,
+    /// Systems in descending order of the chosen centrality
+    pub hubs: Vec<SystemInfo>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JumpEntryPoint {
+    /// System in the main component to jump from
+    pub from: SystemInfo,
+    /// System in this component to jump to
+    pub to: SystemInfo,
+    /// Jump distance in light-years
+    pub distance_ly: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GateComponentInfo {
+    /// Position in the size ranking; 0 is the main component
+    pub index: usize,
+    pub size: usize,
+    pub is_main: bool,
+    /// Regions the component's systems belong to
+    pub region_ids: Vec<u32>,
+    /// Member systems (left empty for the main component)
+    pub systems: Vec<SystemInfo>,
+    /// Shortest jump into this component from the main one (absent for the main component)
+    pub entry_point: Option<JumpEntryPoint>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComponentsResponse {
+    pub components: Vec<GateComponentInfo>,
+    pub total_components: usize,
+    /// Systems without any gate, each a component of its own
+    pub gate_less_systems: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GateBridge {
+    pub from: SystemInfo,
+    pub to: SystemInfo,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChokepointsResponse {
+    /// Region analysed, or absent for the whole network
+    pub region_id: Option<u32>,
+    /// One page of the systems whose loss splits the gate network, sorted by ID
+    pub articulation_points: Vec<SystemInfo>,
+    /// One page of the gate connections whose loss splits the gate network, sorted by ID pair
+    pub bridges: Vec<GateBridge>,
+    /// Articulation points across all pages
+    pub total_articulation_points: usize,
+    /// Bridges across all pages
+    pub total_bridges: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VolumeQueryResponse {
+    /// Systems inside the volume
+    pub systems: Vec<SystemInfo>,
+    /// Number of systems inside the volume before pagination
+    pub total_found: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorridorSystem {
+    /// System inside the corridor; `distance` is its distance from the segment in light-years
+    pub system: SystemInfo,
+    /// Projection onto the segment in light-years from its start; slightly outside
+    /// 0..length for systems in the rounded end caps
+    pub along: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorridorResponse {
+    /// Segment start in meters [x, y, z]
+    pub from: [f64; 3],
+    /// Segment end in meters [x, y, z]
+    pub to: [f64; 3],
+    /// Segment length in light-years
+    pub length: f64,
+    /// Corridor radius in light-years
+    pub radius: f64,
+    /// Systems inside the corridor, ordered along the segment
+    pub systems: Vec<CorridorSystem>,
+    /// Number of systems inside the corridor before pagination
+    pub total_found: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
 // Simplified system data for bulk map requests
 #[derive(Debug, Serialize, ToSchema)]
 pub struct SystemMapData {
@@ -166,6 +396,25 @@ pub struct SystemMapData {
     pub center: [f64; 3],
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapTileResponse {
+    pub level: u8,
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+    /// Lower corner of the tile in meters [x, y, z]
+    pub min: [f64; 3],
+    /// Upper corner of the tile in meters [x, y, z]
+    pub max: [f64; 3],
+    /// Leaf tiles hold every system inside them; coarser tiles hold a representative sample
+    pub leaf: bool,
+    /// Number of systems inside the tile
+    pub total_systems: usize,
+    pub systems: Vec<SystemMapData>,
+    /// Non-empty child tiles at the next level, as [x, y, z]
+    pub children: Vec<[u32; 3]>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct BulkSystemsResponse {
     pub systems: Vec<SystemMapData>,
@@ -174,59 +423,227 @@ pub struct BulkSystemsResponse {
     pub limit: usize,
 }
 
+/// `center_system` is always set when the search center is a system given by
+/// `name` or `id`; it is null only when searching around a raw `x`,`y`,`z` point
 #[derive(Debug, Serialize, ToSchema)]
 pub struct NearbySystemsResponse {
-    pub center_system: SystemInfo,
+    /// System searched around; always set when the center is given by `name` or `id`, null only for an `x`,`y`,`z` point
+    #[schema(required = true)]
+    pub center_system: Option<SystemInfo>,
+    /// Search center in meters [x, y, z]
+    pub center: [f64; 3],
     pub nearby_systems: Vec<SystemInfo>,
     /// Search radius in light-years
     pub radius: f64,
     pub total_found: usize,
 }
 
+/// `center_system` is always set when the search center is a system given by
+/// `name` or `id`; it is null only when searching around a raw `x`,`y`,`z` point
 #[derive(Debug, Serialize, ToSchema)]
 pub struct NearestSystemsResponse {
-    pub center_system: SystemInfo,
+    /// System searched around; always set when the center is given by `name` or `id`, null only for an `x`,`y`,`z` point
+    #[schema(required = true)]
+    pub center_system: Option<SystemInfo>,
+    /// Search center in meters [x, y, z]
+    pub center: [f64; 3],
     pub nearest_systems: Vec<SystemInfo>,
     pub k: usize,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AutocompleteResponse {
+    /// Matching systems, best first
     pub suggestions: Vec<SystemSuggestion>,
+    /// Matching constellations, best first
+    pub constellations: Vec<TerritorySuggestion>,
+    /// Matching regions, best first
+    pub regions: Vec<TerritorySuggestion>,
     pub query: String,
 }
 
+/// What a suggestion or search hit points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    System,
+    Constellation,
+    Region,
+    /// An item type from the type names table
+    TypeName,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct SystemSuggestion {
+    /// Always `system`
+    pub kind: SuggestionKind,
     pub id: u32,
     pub name: String,
+    /// Match quality from 0 to 1: prefix matches score at least 0.7, substring
+    /// matches 0.4 to 0.6 and typo-tolerant matches below 0.4
+    pub score: f64,
+    pub region_id: Option<u32>,
     pub region_name: Option<String>,
+    pub constellation_id: Option<u32>,
     pub constellation_name: Option<String>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TerritorySuggestion {
+    /// `constellation` or `region`
+    pub kind: SuggestionKind,
+    pub id: u32,
+    pub name: String,
+    /// Match quality from 0 to 1, on the same scale as system suggestions
+    pub score: f64,
+    /// Parent region of a constellation (absent for regions)
+    pub region_id: Option<u32>,
+    pub region_name: Option<String>,
+}
+
 // Query parameters
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct NearbyQuery {
-    /// System name to search around
-    pub name: String,
+    /// System name to search around (or give `id` or `x`,`y`,`z` instead)
+    pub name: Option<String>,
     /// Search radius in light years
     pub radius: f64,
 }
 
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct NearestQuery {
-    /// System name to search around
-    pub name: String,
+    /// System name to search around (or give `id` or `x`,`y`,`z` instead)
+    pub name: Option<String>,
     /// Number of nearest systems to return
     pub k: usize,
 }
 
+/// Unit of raw coordinates in a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinateUnit {
+    /// Meters from galactic center, as used in `center` fields
+    #[default]
+    Meters,
+    /// Light-years from galactic center
+    Ly,
+}
+
+/// Alternatives to a system name for the center of a spatial query
+#[derive(Debug, Default, Deserialize, ToSchema, IntoParams)]
+pub struct CenterQuery {
+    /// System ID to search around
+    pub id: Option<u32>,
+    /// X coordinate of a point to search around (with `y` and `z`)
+    pub x: Option<f64>,
+    /// Y coordinate of a point to search around
+    pub y: Option<f64>,
+    /// Z coordinate of a point to search around
+    pub z: Option<f64>,
+    /// Unit of `x`, `y` and `z`: meters (default) or ly
+    pub unit: Option<CoordinateUnit>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct BoxQuery {
+    /// Lower X bound of the box (before rotation)
+    pub min_x: f64,
+    /// Lower Y bound of the box (before rotation)
+    pub min_y: f64,
+    /// Lower Z bound of the box (before rotation)
+    pub min_z: f64,
+    /// Upper X bound of the box (before rotation)
+    pub max_x: f64,
+    /// Upper Y bound of the box (before rotation)
+    pub max_y: f64,
+    /// Upper Z bound of the box (before rotation)
+    pub max_z: f64,
+    /// Unit of the bounds: meters (default) or ly
+    pub unit: Option<CoordinateUnit>,
+    /// Rotation quaternion X component; give all of qx, qy, qz, qw to rotate the box about its center
+    pub qx: Option<f64>,
+    /// Rotation quaternion Y component
+    pub qy: Option<f64>,
+    /// Rotation quaternion Z component
+    pub qz: Option<f64>,
+    /// Rotation quaternion W component
+    pub qw: Option<f64>,
+    /// Maximum number of systems to return (default: 1000, max: 5000)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct FrustumQuery {
+    /// Camera X position
+    pub x: f64,
+    /// Camera Y position
+    pub y: f64,
+    /// Camera Z position
+    pub z: f64,
+    /// Unit of the position and clip distances: meters (default) or ly
+    pub unit: Option<CoordinateUnit>,
+    /// Camera orientation quaternion X component; without qx, qy, qz, qw the camera looks down -Z with +Y up
+    pub qx: Option<f64>,
+    /// Camera orientation quaternion Y component
+    pub qy: Option<f64>,
+    /// Camera orientation quaternion Z component
+    pub qz: Option<f64>,
+    /// Camera orientation quaternion W component
+    pub qw: Option<f64>,
+    /// Vertical field of view in degrees
+    pub fov: f64,
+    /// Viewport width over height (default: 1)
+    pub aspect: Option<f64>,
+    /// Near clip distance
+    pub near: f64,
+    /// Far clip distance
+    pub far: f64,
+    /// Maximum number of systems to return, nearest the camera first (default: 1000, max: 5000)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct CorridorQuery {
+    /// Start system name or ID; alternatively give from_x, from_y, from_z
+    pub from: Option<String>,
+    /// X coordinate of the start point
+    pub from_x: Option<f64>,
+    /// Y coordinate of the start point
+    pub from_y: Option<f64>,
+    /// Z coordinate of the start point
+    pub from_z: Option<f64>,
+    /// End system name or ID; alternatively give to_x, to_y, to_z
+    pub to: Option<String>,
+    /// X coordinate of the end point
+    pub to_x: Option<f64>,
+    /// Y coordinate of the end point
+    pub to_y: Option<f64>,
+    /// Z coordinate of the end point
+    pub to_z: Option<f64>,
+    /// Unit of the point coordinates: meters (default) or ly
+    pub unit: Option<CoordinateUnit>,
+    /// Corridor radius in light years
+    pub radius: f64,
+    /// Maximum number of systems to return (default: 1000, max: 5000)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct AutocompleteQuery {
-    /// Search query for system names
+    /// Search query for system names (at most 64 characters); case, spaces and punctuation are ignored and small typos tolerated
     pub q: String,
-    /// Maximum number of suggestions (max 50)
+    /// Maximum number of suggestions of each kind (max 50)
     pub limit: Option<usize>,
+    /// Only suggest systems and constellations in this region (and the region itself)
+    pub region_id: Option<u32>,
+    /// Only suggest systems in this constellation (and the constellation and its region)
+    pub constellation_id: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
@@ -259,6 +676,172 @@ pub struct BulkConnectionsQuery {
     pub connection_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct RouteQuery {
+    /// Origin system name or ID
+    pub from: String,
+    /// Destination system name or ID
+    pub to: String,
+}
+
+/// Centrality measure used to rank hubs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CentralityMetric {
+    #[default]
+    Betweenness,
+    Closeness,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct HubsQuery {
+    /// Only rank systems in this region (scores are still network-wide)
+    pub region_id: Option<u32>,
+    /// Maximum number of systems to return (default: 20, max: 500)
+    pub limit: Option<usize>,
+    /// Ranking measure: betweenness (default) or closeness
+    pub metric: Option<CentralityMetric>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ComponentsQuery {
+    /// Maximum number of components to return (default: 100, max: 1000)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ChokepointsQuery {
+    /// Restrict the analysis to one region's internal gates (default: whole network)
+    pub region_id: Option<u32>,
+    /// Maximum number of articulation points and of bridges to return (default: 100, max: 1000)
+    pub limit: Option<usize>,
+    /// Offset into both lists for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ReachableQuery {
+    /// Origin system name or ID
+    pub name: String,
+    /// Total hop budget (max 20); gate jumps and jump drive hops each count as one hop
+    pub max_jumps: u32,
+    /// Jump drive range in light-years (max 10); when set, jump drive hops are allowed alongside gates
+    pub max_jump_ly: Option<f64>,
+    /// Maximum number of systems to return (default: 500, max: 1000)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct AlternativeRoutesQuery {
+    /// Origin system name or ID
+    pub from: String,
+    /// Destination system name or ID
+    pub to: String,
+    /// Number of routes to return including the best one (default: 3, max: 10)
+    pub k: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct TourQuery {
+    /// Comma-separated system names or IDs to visit
+    pub systems: String,
+    /// System name or ID the tour must start from
+    pub start: Option<String>,
+    /// System name or ID the tour must finish at (may equal `start` for a round trip)
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct RendezvousQuery {
+    /// Comma-separated starting system names or IDs, one per pilot
+    pub systems: String,
+    /// What to minimise across pilots: max (default) or total
+    pub objective: Option<RendezvousObjective>,
+    /// Systems nearest the pilots' centroid to consider as meeting points (default: 250, max: 2000)
+    pub candidates: Option<usize>,
+    /// Number of meeting systems to return (default: 10, max: 100)
+    pub limit: Option<usize>,
+}
+
+/// How a route is allowed to move between systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteMode {
+    /// Stargates only
+    #[default]
+    Gate,
+    /// Jump drive hops to any system within `max_jump_ly`
+    Jump,
+    /// Stargates, jump bridges and wormholes combined with jump drive hops
+    Mixed,
+}
+
+/// Kind of edge a route uses to move between two systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteLink {
+    Stargate,
+    JumpBridge,
+    Wormhole,
+    /// Jump drive hop
+    Jump,
+}
+
+/// How per-pilot costs combine when ranking meeting systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RendezvousObjective {
+    /// Minimise the cost of the pilot with the longest trip
+    #[default]
+    Max,
+    /// Minimise the cost summed over every pilot
+    Total,
+}
+
+/// What a route planner minimises
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteOptimize {
+    /// Fewest jumps
+    #[default]
+    Jumps,
+    /// Shortest total distance travelled
+    Distance,
+}
+
+/// Options shared by the route planning endpoints
+#[derive(Debug, Default, Deserialize, ToSchema, IntoParams)]
+pub struct RouteParams {
+    /// Travel mode: gate (default), jump or mixed
+    pub mode: Option<RouteMode>,
+    /// Maximum jump range in light-years, at most 10 (required for jump and mixed modes)
+    pub max_jump_ly: Option<f64>,
+    /// Optimisation target: jumps (default) or distance
+    pub optimize: Option<RouteOptimize>,
+    /// Cost multiplier for stargate jumps (default: 1)
+    pub stargate_weight: Option<f64>,
+    /// Cost multiplier for jump bridge jumps (default: 1)
+    pub jump_bridge_weight: Option<f64>,
+    /// Cost multiplier for wormhole transits (default: 1)
+    pub wormhole_weight: Option<f64>,
+    /// Cost multiplier for jump drive hops (default: 1)
+    pub jump_weight: Option<f64>,
+    /// Comma-separated system names or IDs the route must not pass through
+    pub avoid_systems: Option<String>,
+    /// Comma-separated constellation IDs the route must not pass through
+    pub avoid_constellations: Option<String>,
+    /// Comma-separated region IDs the route must not pass through
+    pub avoid_regions: Option<String>,
+    /// Comma-separated security classes the route must not pass through
+    pub avoid_security_classes: Option<String>,
+    /// Comma-separated faction IDs whose systems the route must not pass through
+    pub avoid_factions: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TypeName {
     pub type_id: u32,
@@ -278,4 +861,274 @@ pub struct TypeNameResponse {
     pub type_names: Vec<TypeName>,
     pub query: String,
     pub total_found: usize,
-} 
\ No newline at end of file
+} 
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SearchQuery {
+    /// Search text (at most 64 characters); case, spaces and punctuation are ignored and small typos tolerated
+    pub q: String,
+    /// Maximum number of hits across all kinds (default: 20, max: 100)
+    pub limit: Option<usize>,
+    /// Comma-separated kinds to search: system, constellation, region, type_name (default: all)
+    pub kinds: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchHit {
+    pub kind: SuggestionKind,
+    /// System, constellation, region or type ID, depending on `kind`
+    pub id: u32,
+    pub name: String,
+    /// Match quality from 0 to 1, comparable across kinds: prefix matches score
+    /// at least 0.7, substring matches 0.4 to 0.6 and typo-tolerant matches below 0.4
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchResponse {
+    /// Hits of every requested kind, best first
+    pub hits: Vec<SearchHit>,
+    pub query: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HullVertex {
+    pub system_id: u32,
+    /// Coordinates in meters [x, y, z]
+    pub position: [f64; 3],
+}
+
+/// Convex hull of a territory's systems
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HullInfo {
+    pub vertices: Vec<HullVertex>,
+    /// Triangles as indices into `vertices`, counter-clockwise seen from outside; empty when all systems are coplanar
+    pub faces: Vec<[u32; 3]>,
+}
+
+/// Shape of a region or constellation, from the centres of its systems
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GeometryInfo {
+    /// Mean position of the member systems in meters [x, y, z]
+    pub center: [f64; 3],
+    /// Lower corner of the axis-aligned bounds in meters [x, y, z]
+    pub min: [f64; 3],
+    /// Upper corner of the axis-aligned bounds in meters [x, y, z]
+    pub max: [f64; 3],
+    /// Distance from the center to the farthest member system in light-years
+    pub radius: f64,
+    pub hull: HullInfo,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegionDetails {
+    pub id: u32,
+    pub name: Option<String>,
+    pub solar_system_ids: Vec<u32>,
+    pub constellation_ids: Vec<u32>,
+    /// Absent for regions without systems
+    pub geometry: Option<GeometryInfo>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConstellationDetails {
+    pub id: u32,
+    pub name: String,
+    pub region_id: u32,
+    pub region_name: Option<String>,
+    pub solar_system_ids: Vec<u32>,
+    /// Absent for constellations without systems
+    pub geometry: Option<GeometryInfo>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DistanceMatrixRequest {
+    /// System names or IDs (max 200); the matrix follows this order
+    pub systems: Vec<String>,
+    /// Also return the fewest gate jumps between each pair (default: false)
+    pub include_hops: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DistanceMatrixResponse {
+    /// Resolved systems in request order
+    pub systems: Vec<SystemInfo>,
+    /// Straight-line distances in light-years; `distances[i][j]` is from `systems[i]` to `systems[j]`
+    pub distances: Vec<Vec<f64>>,
+    /// Fewest gate jumps between each pair, null where unreachable (only when requested)
+    pub hops: Option<Vec<Vec<Option<u32>>>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SystemSearchQuery {
+    /// Only systems with at least this many planets of a type, as comma-separated
+    /// `planet_type_id:count` pairs (e.g. `11:2,12:1`)
+    pub planets: Option<String>,
+    /// Comma-separated security classes to include (case-insensitive)
+    pub security_class: Option<String>,
+    /// Lowest security status to include
+    pub min_security: Option<f64>,
+    /// Highest security status to include
+    pub max_security: Option<f64>,
+    /// Comma-separated faction IDs to include
+    pub faction_id: Option<String>,
+    /// Comma-separated sovereignty values to include (case-insensitive)
+    pub sovereignty: Option<String>,
+    /// Only systems with (true) or without (false) a star
+    pub has_star: Option<bool>,
+    /// Comma-separated anchoring categories the system must allow
+    pub allows_anchor_category: Option<String>,
+    /// Comma-separated anchoring groups the system must allow
+    pub allows_anchor_group: Option<String>,
+    pub region_id: Option<u32>,
+    pub constellation_id: Option<u32>,
+    /// Only systems within this many light-years of a center given by `name`, `id` or `x`,`y`,`z`
+    pub radius: Option<f64>,
+    /// Center system name for `radius`
+    pub name: Option<String>,
+    /// Maximum number of systems to return (default: 100, max: 1000)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SystemSearchResponse {
+    /// Matching systems, nearest first with a radius (with `distance` in light-years), otherwise by ID
+    pub systems: Vec<SystemInfo>,
+    /// Matching systems across all pages
+    pub total_matches: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SystemCluster {
+    /// Rank of the cluster by size, largest first
+    pub id: u32,
+    /// Number of member systems; list them through `systems` in the response
+    pub size: usize,
+    /// Number of members with at least `min_points` systems within `eps`
+    pub core_count: usize,
+    /// Mean position of the members in meters [x, y, z]
+    pub center: [f64; 3],
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClusteredSystem {
+    pub system_id: u32,
+    /// Cluster the system belongs to, or absent for noise
+    pub cluster: Option<u32>,
+    /// Other systems within `density_radius` (only when densities are requested)
+    pub neighbours: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClustersResponse {
+    /// Neighbourhood radius in light-years
+    pub eps: f64,
+    pub min_points: usize,
+    /// Radius used for local density in light-years
+    pub density_radius: f64,
+    pub clusters: Vec<SystemCluster>,
+    /// Number of systems in no cluster
+    pub noise_count: usize,
+    /// One page of the systems considered, sorted by ID, with their cluster
+    pub systems: Vec<ClusteredSystem>,
+    /// Systems considered across all pages
+    pub total_systems: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ClustersQuery {
+    /// Neighbourhood radius in light-years (max 10)
+    pub eps: f64,
+    /// Systems (including itself) a system needs within `eps` to be a cluster core
+    pub min_points: usize,
+    /// Radius for per-system local density in light-years (default: eps, max 10)
+    pub density_radius: Option<f64>,
+    /// Only cluster systems in this region
+    pub region_id: Option<u32>,
+    /// Also return the local density of each listed system (default: false)
+    pub include_densities: Option<bool>,
+    /// Maximum number of systems to list (default: 1000, max: 5000)
+    pub limit: Option<usize>,
+    /// Offset into the system list for pagination (default: 0)
+    pub offset: Option<usize>,
+}
+
+/// A region or constellation matched to a point
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TerritoryMatchInfo {
+    pub id: u32,
+    pub name: Option<String>,
+    /// Whether the point lies inside the territory's convex hull; otherwise it was matched by nearest system
+    pub inside_hull: bool,
+    /// Closest member system; `distance` is from the point in light-years
+    pub nearest_system: SystemInfo,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TerritoryLookupResponse {
+    /// Looked-up point in meters [x, y, z]
+    pub point: [f64; 3],
+    pub region: Option<TerritoryMatchInfo>,
+    /// Always a constellation of `region`
+    pub constellation: Option<TerritoryMatchInfo>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct TerritoryLookupQuery {
+    /// X coordinate of the point
+    pub x: f64,
+    /// Y coordinate of the point
+    pub y: f64,
+    /// Z coordinate of the point
+    pub z: f64,
+    /// Unit of the coordinates: meters (default) or ly
+    pub unit: Option<CoordinateUnit>,
+}
+
+/// How a region is flattened to 2D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMethod {
+    /// Galactic (x, z) coordinates, looking down the Y axis
+    #[default]
+    TopDown,
+    /// Projection onto the region's best-fit plane, centred on its centroid
+    Pca,
+    /// Force-directed layout of the region's gate graph
+    ForceDirected,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct LayoutQuery {
+    /// Projection to use: top_down (default), pca or force_directed
+    pub method: Option<LayoutMethod>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LayoutPosition {
+    pub id: u32,
+    pub name: String,
+    /// Projected position in light-years [x, y]
+    pub position: [f64; 2],
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegionLayoutResponse {
+    pub region_id: u32,
+    pub region_name: Option<String>,
+    pub method: LayoutMethod,
+    /// Member systems sorted by ID
+    pub systems: Vec<LayoutPosition>,
+    /// Gates between member systems as [lower ID, higher ID] pairs
+    pub gates: Vec<[u32; 2]>,
+    /// Lower corner of the layout [x, y]
+    pub min: [f64; 2],
+    /// Upper corner of the layout [x, y]
+    pub max: [f64; 2],
+}
\ No newline at end of file
@@ -0,0 +1,208 @@
+use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
+
+use super::{SpatialIndex, SystemId};
+
+/// A group of systems found by DBSCAN
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    /// Member systems sorted by ID
+    pub systems: Vec<SystemId>,
+    /// Members with at least `min_points` systems within `eps`
+    pub core: Vec<SystemId>,
+}
+
+/// Result of a DBSCAN run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Clustering {
+    /// Clusters, largest first (ties broken by lowest member ID)
+    pub clusters: Vec<Cluster>,
+    /// Systems in no cluster, sorted by ID
+    pub noise: Vec<SystemId>,
+}
+
+impl SpatialIndex {
+    /// Systems within `radius` meters of a system, restricted to `members`
+    /// when given; the system itself is included
+    fn neighbourhood(
+        &self,
+        system_id: SystemId,
+        radius: f64,
+        members: Option<&FxHashMap<SystemId, usize>>,
+    ) -> Vec<SystemId> {
+        let Some(system) = self.systems.get(&system_id) else {
+            return Vec::new();
+        };
+        self.find_systems_within_radius(system.center, radius)
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|id| members.is_none_or(|members| members.contains_key(id)))
+            .collect()
+    }
+
+    /// DBSCAN over `systems` (all systems when `None`) with neighbourhoods of
+    /// `eps` meters. A system is a core point when at least `min_points`
+    /// systems, itself included, lie within `eps`.
+    pub fn cluster_systems(&self, systems: Option<&[SystemId]>, eps: f64, min_points: usize) -> Clustering {
+        let mut members: Vec<SystemId> = match systems {
+            Some(systems) => systems.to_vec(),
+            None => self.systems.keys().copied().collect(),
+        };
+        members.sort_unstable();
+        members.dedup();
+        let slot: FxHashMap<SystemId, usize> = members.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let restrict = systems.map(|_| &slot);
+
+        let mut label: Vec<Option<usize>> = vec![None; members.len()];
+        let mut visited = vec![false; members.len()];
+        let mut is_core = vec![false; members.len()];
+        let mut cluster_count = 0;
+
+        for start in 0..members.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let neighbours = self.neighbourhood(members[start], eps, restrict);
+            if neighbours.len() < min_points {
+                continue;
+            }
+
+            let cluster = cluster_count;
+            cluster_count += 1;
+            label[start] = Some(cluster);
+            is_core[start] = true;
+
+            // Systems are marked visited as they are queued, so each one is
+            // queued and expanded at most once
+            let mut queue: VecDeque<usize> = VecDeque::new();
+            let mut reached = neighbours;
+            loop {
+                for id in reached.drain(..) {
+                    let i = slot[&id];
+                    if label[i].is_none() {
+                        label[i] = Some(cluster);
+                    }
+                    if !visited[i] {
+                        visited[i] = true;
+                        queue.push_back(i);
+                    }
+                }
+
+                // Only core points spread the cluster further
+                let Some(i) = queue.pop_front() else {
+                    break;
+                };
+                let neighbours = self.neighbourhood(members[i], eps, restrict);
+                if neighbours.len() >= min_points {
+                    is_core[i] = true;
+                    reached = neighbours;
+                }
+            }
+        }
+
+        let mut clusters = vec![
+            Cluster {
+                systems: Vec::new(),
+                core: Vec::new(),
+            };
+            cluster_count
+        ];
+        let mut noise = Vec::new();
+        for (i, &id) in members.iter().enumerate() {
+            match label[i] {
+                Some(cluster) => {
+                    clusters[cluster].systems.push(id);
+                    if is_core[i] {
+                        clusters[cluster].core.push(id);
+                    }
+                }
+                None => noise.push(id),
+            }
+        }
+        clusters.sort_by(|a, b| b.systems.len().cmp(&a.systems.len()).then_with(|| a.systems[0].cmp(&b.systems[0])));
+
+        Clustering { clusters, noise }
+    }
+
+    /// Number of other systems within `radius` meters of each of `systems`
+    /// (all systems when `None`), sorted by system ID. Only the page of
+    /// `limit` systems after `offset` is counted.
+    pub fn local_density(
+        &self,
+        systems: Option<&[SystemId]>,
+        radius: f64,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<(SystemId, usize)> {
+        let mut members: Vec<SystemId> = match systems {
+            Some(systems) => systems.to_vec(),
+            None => self.systems.keys().copied().collect(),
+        };
+        members.sort_unstable();
+        members.dedup();
+        let slot: FxHashMap<SystemId, usize> = members.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let restrict = systems.map(|_| &slot);
+
+        members
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|&id| (id, self.neighbourhood(id, radius, restrict).len().saturating_sub(1)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::{index_with_gates, system};
+    use crate::coordinates::Distance;
+
+    fn ly(value: f64) -> f64 {
+        Distance::from_light_years(value).to_meters()
+    }
+
+    #[test]
+    fn test_dbscan_finds_dense_groups_and_noise() {
+        let index = index_with_gates(
+            vec![
+                // A chain of four 1 ly apart
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+                system(4, "D", [3.0, 0.0, 0.0]),
+                // A tight triangle far away
+                system(5, "E", [50.0, 0.0, 0.0]),
+                system(6, "F", [50.5, 0.0, 0.0]),
+                system(7, "G", [50.0, 0.5, 0.0]),
+                // Loners
+                system(8, "H", [20.0, 0.0, 0.0]),
+                system(9, "I", [3.0, 5.0, 0.0]),
+            ],
+            &[],
+        );
+
+        let clustering = index.cluster_systems(None, ly(1.1), 3);
+        assert_eq!(clustering.clusters.len(), 2);
+        assert_eq!(clustering.clusters[0].systems, vec![1, 2, 3, 4]);
+        // The chain ends have only one neighbour, so they border the cluster
+        assert_eq!(clustering.clusters[0].core, vec![2, 3]);
+        assert_eq!(clustering.clusters[1].systems, vec![5, 6, 7]);
+        assert_eq!(clustering.noise, vec![8, 9]);
+
+        // Restricting to a subset ignores neighbours outside it
+        let subset = index.cluster_systems(Some(&[1, 2, 4, 8]), ly(1.1), 2);
+        assert_eq!(subset.clusters.len(), 1);
+        assert_eq!(subset.clusters[0].systems, vec![1, 2]);
+        assert_eq!(subset.noise, vec![4, 8]);
+
+        let density = index.local_density(None, ly(1.1), 0, usize::MAX);
+        assert_eq!(density[0], (1, 1));
+        assert_eq!(density[1], (2, 2));
+        assert_eq!(density[4], (5, 2));
+        assert_eq!(density[7], (8, 0));
+
+        // Pages are taken in system ID order
+        assert_eq!(index.local_density(None, ly(1.1), 4, 2), vec![(5, 2), (6, 2)]);
+    }
+}
@@ -0,0 +1,170 @@
+use rustc_hash::FxHashSet;
+
+use super::{Point3D, SpatialIndex, SystemId};
+use crate::models::SolarSystem;
+
+/// Attribute conditions a system must meet. Empty sets and `None` match
+/// every system; all given conditions must hold together.
+#[derive(Debug, Clone, Default)]
+pub struct SystemFilter {
+    /// Minimum planet count per planet type ID
+    pub min_planets: Vec<(String, u32)>,
+    /// Compared case-insensitively; store lowercase
+    pub security_classes: FxHashSet<String>,
+    pub min_security: Option<f64>,
+    pub max_security: Option<f64>,
+    pub factions: FxHashSet<u32>,
+    /// Compared case-insensitively; store lowercase
+    pub sovereignties: FxHashSet<String>,
+    pub has_star: Option<bool>,
+    /// Anchoring categories that must not be disallowed
+    pub anchor_categories: Vec<String>,
+    /// Anchoring groups that must not be disallowed
+    pub anchor_groups: Vec<String>,
+    pub region_id: Option<u32>,
+    pub constellation_id: Option<u32>,
+}
+
+impl SystemFilter {
+    pub fn matches(&self, system: &SolarSystem) -> bool {
+        let planets = &system.celestials.planet_count_by_type;
+        if !self
+            .min_planets
+            .iter()
+            .all(|(planet_type, min)| planets.get(planet_type).copied().unwrap_or(0) >= *min)
+        {
+            return false;
+        }
+
+        if !self.security_classes.is_empty()
+            && !system
+                .security
+                .class
+                .as_ref()
+                .is_some_and(|class| self.security_classes.contains(&class.to_lowercase()))
+        {
+            return false;
+        }
+
+        if self.min_security.is_some() || self.max_security.is_some() {
+            // Systems without a parseable status never match a status range
+            let Some(status) = system.security.status.as_deref().and_then(|s| s.trim().parse::<f64>().ok()) else {
+                return false;
+            };
+            if self.min_security.is_some_and(|min| status < min) || self.max_security.is_some_and(|max| status > max) {
+                return false;
+            }
+        }
+
+        if !self.factions.is_empty() && !system.metadata.faction_id.is_some_and(|id| self.factions.contains(&id)) {
+            return false;
+        }
+
+        if !self.sovereignties.is_empty()
+            && !system
+                .metadata
+                .sovereignty
+                .as_ref()
+                .is_some_and(|sovereignty| self.sovereignties.contains(&sovereignty.to_lowercase()))
+        {
+            return false;
+        }
+
+        if self.has_star.is_some_and(|wanted| system.celestials.star_id.is_some() != wanted) {
+            return false;
+        }
+
+        let metadata = &system.metadata;
+        if self.anchor_categories.iter().any(|c| metadata.disallowed_anchor_categories.contains(c))
+            || self.anchor_groups.iter().any(|g| metadata.disallowed_anchor_groups.contains(g))
+        {
+            return false;
+        }
+
+        self.region_id.is_none_or(|id| system.region_id == Some(id))
+            && self.constellation_id.is_none_or(|id| system.constellation_id == Some(id))
+    }
+}
+
+impl SpatialIndex {
+    /// Systems meeting `filter`, optionally only those within `radius` meters
+    /// of `center`. With a center, results are nearest first and carry their
+    /// distance in meters; otherwise they are sorted by ID.
+    pub fn filter_systems(&self, filter: &SystemFilter, within: Option<(Point3D, f64)>) -> Vec<(SystemId, Option<f64>)> {
+        let mut found: Vec<(SystemId, Option<f64>)> = match within {
+            Some((center, radius)) => {
+                let mut nearby = self.find_systems_within_radius(center, radius);
+                nearby.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+                nearby.into_iter().map(|(id, distance)| (id, Some(distance))).collect()
+            }
+            None => {
+                let mut ids: Vec<SystemId> = self.systems.keys().copied().collect();
+                ids.sort_unstable();
+                ids.into_iter().map(|id| (id, None)).collect()
+            }
+        };
+
+        found.retain(|(id, _)| self.systems.get(id).is_some_and(|system| filter.matches(system)));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::{index_with_gates, system};
+    use super::SystemFilter;
+    use crate::coordinates::Distance;
+
+    #[test]
+    fn test_filter_systems_by_attributes_and_radius() {
+        let mut systems = vec![
+            system(1, "A", [0.0, 0.0, 0.0]),
+            system(2, "B", [1.0, 0.0, 0.0]),
+            system(3, "C", [2.0, 0.0, 0.0]),
+            system(4, "D", [30.0, 0.0, 0.0]),
+        ];
+        let planets = [[("11", 3), ("12", 0)], [("11", 1), ("12", 2)], [("11", 2), ("12", 1)], [("11", 4), ("12", 4)]];
+        let statuses = ["-0.52", "0.65", "0.9", "0.1"];
+        for (i, (_, solar_system, _)) in systems.iter_mut().enumerate() {
+            solar_system.celestials.planet_count_by_type =
+                planets[i].iter().map(|&(planet_type, count)| (planet_type.to_string(), count)).collect();
+            solar_system.security.status = Some(statuses[i].to_string());
+            solar_system.security.class = Some(if i % 2 == 0 { "A" } else { "B" }.to_string());
+            solar_system.metadata.faction_id = Some(500_000 + i as u32 % 2);
+        }
+        systems[1].1.celestials.star_id = Some(40_000_002);
+        systems[2].1.metadata.disallowed_anchor_categories = vec!["23".to_string()];
+        let index = index_with_gates(systems, &[]);
+        let ids = |found: Vec<(u32, Option<f64>)>| found.into_iter().map(|(id, _)| id).collect::<Vec<u32>>();
+
+        let two_temperate = SystemFilter {
+            min_planets: vec![("11".to_string(), 2)],
+            ..SystemFilter::default()
+        };
+        assert_eq!(ids(index.filter_systems(&two_temperate, None)), vec![1, 3, 4]);
+
+        let secure_class_a = SystemFilter {
+            security_classes: ["a".to_string()].into_iter().collect(),
+            min_security: Some(0.0),
+            ..SystemFilter::default()
+        };
+        assert_eq!(ids(index.filter_systems(&secure_class_a, None)), vec![3]);
+
+        let anchorable_starless = SystemFilter {
+            anchor_categories: vec!["23".to_string()],
+            has_star: Some(false),
+            factions: [500_000].into_iter().collect(),
+            ..SystemFilter::default()
+        };
+        assert_eq!(ids(index.filter_systems(&anchorable_starless, None)), vec![1]);
+
+        // A radius keeps nearby matches only, nearest first
+        let center = index.get_system(3).unwrap().center;
+        let radius = Distance::from_light_years(1.5).to_meters();
+        let nearby = index.filter_systems(&two_temperate, Some((center, radius)));
+        assert_eq!(ids(nearby), vec![3]);
+        let all_nearby = index.filter_systems(&SystemFilter::default(), Some((center, radius)));
+        assert_eq!(ids(all_nearby.clone()), vec![3, 2]);
+        assert!((Distance::from_meters(all_nearby[1].1.unwrap()).to_ly() - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,559 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use super::{Point3D, SpatialIndex, SystemId};
+use crate::models::{Constellation, Region, SolarSystem};
+
+/// Convex hull of a set of systems
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConvexHull {
+    /// Systems on the hull, sorted by ID
+    pub vertices: Vec<SystemId>,
+    /// Triangles as indices into `vertices`, wound counter-clockwise seen from
+    /// outside. Empty when the systems are all coplanar.
+    pub faces: Vec<[u32; 3]>,
+}
+
+/// Shape of a region or constellation, from the centres of its systems
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Geometry {
+    /// Mean position of the member systems in meters
+    pub centroid: Point3D,
+    /// Lower corner of the axis-aligned bounds in meters
+    pub min: Point3D,
+    /// Upper corner of the axis-aligned bounds in meters
+    pub max: Point3D,
+    /// Distance from the centroid to the farthest member in meters
+    pub radius: f64,
+    pub hull: ConvexHull,
+}
+
+/// Geometry of every region and constellation that has systems
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerritoryGeometry {
+    pub regions: FxHashMap<u32, Geometry>,
+    pub constellations: FxHashMap<u32, Geometry>,
+}
+
+impl TerritoryGeometry {
+    /// Fill membership lists and centres of `regions` and `constellations`
+    /// from the systems, and compute their geometry
+    pub fn compute(
+        systems: &FxHashMap<SystemId, SolarSystem>,
+        regions: &mut FxHashMap<u32, Region>,
+        constellations: &mut FxHashMap<u32, Constellation>,
+    ) -> Self {
+        let mut members: Vec<(&SystemId, &SolarSystem)> = systems.iter().collect();
+        members.sort_unstable_by_key(|(id, _)| **id);
+
+        for (&id, system) in members {
+            if let Some(region) = system.region_id.and_then(|region_id| regions.get_mut(&region_id)) {
+                region.solar_system_ids.push(id);
+            }
+            if let Some(constellation) = system
+                .constellation_id
+                .and_then(|constellation_id| constellations.get_mut(&constellation_id))
+            {
+                constellation.solar_system_ids.push(id);
+            }
+        }
+
+        let mut by_region: Vec<(u32, u32)> = constellations
+            .values()
+            .map(|constellation| (constellation.region_id, constellation.id))
+            .collect();
+        by_region.sort_unstable();
+        for (region_id, constellation_id) in by_region {
+            if let Some(region) = regions.get_mut(&region_id) {
+                region.constellation_ids.push(constellation_id);
+            }
+        }
+
+        let shape = |ids: &[SystemId]| -> Option<Geometry> {
+            let points: Vec<(Point3D, SystemId)> = ids
+                .iter()
+                .map(|id| (systems[id].center, *id))
+                .collect();
+            Geometry::of(&points)
+        };
+
+        let mut geometry = Self::default();
+        for (&region_id, region) in regions.iter_mut() {
+            if let Some(shape) = shape(&region.solar_system_ids) {
+                region.center = shape.centroid;
+                geometry.regions.insert(region_id, shape);
+            }
+        }
+        for (&constellation_id, constellation) in constellations.iter() {
+            if let Some(shape) = shape(&constellation.solar_system_ids) {
+                geometry.constellations.insert(constellation_id, shape);
+            }
+        }
+        geometry
+    }
+}
+
+impl Geometry {
+    /// Geometry of a set of system positions, or `None` if there are none
+    pub fn of(points: &[(Point3D, SystemId)]) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+
+        let count = points.len() as f64;
+        let centroid: Point3D = std::array::from_fn(|i| points.iter().map(|(p, _)| p[i]).sum::<f64>() / count);
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for (position, _) in points {
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+        }
+        let radius = points
+            .iter()
+            .map(|(position, _)| super::routing::euclidean(*position, centroid))
+            .fold(0.0, f64::max);
+
+        Some(Self {
+            centroid,
+            min,
+            max,
+            radius,
+            hull: convex_hull(points, centroid, radius),
+        })
+    }
+}
+
+/// Incremental 3D convex hull.
+///
+/// Points are taken relative to `centroid` and scaled by `radius` so the
+/// tolerance does not depend on galactic coordinates. Coplanar inputs produce
+/// a hull with no faces whose vertices are every distinct point.
+fn convex_hull(points: &[(Point3D, SystemId)], centroid: Point3D, radius: f64) -> ConvexHull {
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable_by_key(|&(_, id)| id);
+    let scale = if radius > 0.0 { radius } else { 1.0 };
+    let local: Vec<Point3D> = sorted
+        .iter()
+        .map(|(position, _)| std::array::from_fn(|i| (position[i] - centroid[i]) / scale))
+        .collect();
+    let eps = 1e-9;
+
+    let Some(seed) = initial_tetrahedron(&local, eps) else {
+        let mut vertices: Vec<SystemId> = sorted.iter().map(|&(_, id)| id).collect();
+        vertices.dedup();
+        return ConvexHull {
+            vertices,
+            faces: Vec::new(),
+        };
+    };
+
+    let inside: Point3D = std::array::from_fn(|i| seed.iter().map(|&v| local[v][i]).sum::<f64>() / 4.0);
+    let mut faces: Vec<[usize; 3]> = Vec::new();
+    for [a, b, c] in [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]] {
+        let mut face = [seed[a], seed[b], seed[c]];
+        if plane_distance(&local, face, inside) > 0.0 {
+            face.swap(1, 2);
+        }
+        faces.push(face);
+    }
+
+    for point in 0..local.len() {
+        if seed.contains(&point) {
+            continue;
+        }
+        let visible: Vec<bool> = faces
+            .iter()
+            .map(|&face| plane_distance(&local, face, local[point]) > eps)
+            .collect();
+        if !visible.contains(&true) {
+            continue;
+        }
+
+        // Edges of visible faces whose reverse belongs to a hidden face
+        let mut hidden_edges: FxHashSet<(usize, usize)> = FxHashSet::default();
+        for (face, _) in faces.iter().zip(&visible).filter(|(_, &seen)| !seen) {
+            for k in 0..3 {
+                hidden_edges.insert((face[k], face[(k + 1) % 3]));
+            }
+        }
+        let mut next: Vec<[usize; 3]> = Vec::with_capacity(faces.len() + 2);
+        for (face, seen) in faces.iter().zip(&visible) {
+            if !seen {
+                next.push(*face);
+                continue;
+            }
+            for k in 0..3 {
+                let (a, b) = (face[k], face[(k + 1) % 3]);
+                if hidden_edges.contains(&(b, a)) {
+                    next.push([a, b, point]);
+                }
+            }
+        }
+        faces = next;
+    }
+
+    // Renumber onto the hull's own vertex list
+    let mut used: Vec<usize> = faces.iter().flatten().copied().collect();
+    used.sort_unstable();
+    used.dedup();
+    let slot: FxHashMap<usize, u32> = used.iter().enumerate().map(|(i, &v)| (v, i as u32)).collect();
+
+    let mut faces: Vec<[u32; 3]> = faces
+        .into_iter()
+        .map(|face| {
+            // Rotate so the lowest index leads, keeping the winding
+            let lead = (0..3).min_by_key(|&k| face[k]).unwrap_or(0);
+            std::array::from_fn(|k| slot[&face[(lead + k) % 3]])
+        })
+        .collect();
+    faces.sort_unstable();
+
+    ConvexHull {
+        vertices: used.into_iter().map(|v| sorted[v].1).collect(),
+        faces,
+    }
+}
+
+/// Four points spanning a volume: the first point, the farthest from it, the
+/// farthest from that line and the farthest from that plane
+fn initial_tetrahedron(points: &[Point3D], eps: f64) -> Option<[usize; 4]> {
+    let farthest = |score: &dyn Fn(Point3D) -> f64| {
+        (0..points.len())
+            .map(|i| (i, score(points[i])))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|&(_, value)| value > eps)
+            .map(|(i, _)| i)
+    };
+
+    let a = 0;
+    let b = farthest(&|p| length(sub(p, points[a])))?;
+    let ab = sub(points[b], points[a]);
+    let c = farthest(&|p| length(cross(ab, sub(p, points[a]))) / length(ab))?;
+    let normal = cross(ab, sub(points[c], points[a]));
+    let d = farthest(&|p| dot(normal, sub(p, points[a])).abs() / length(normal))?;
+    Some([a, b, c, d])
+}
+
+/// Signed distance of `point` above the plane of `face` (positive on the side
+/// its counter-clockwise normal points to)
+fn plane_distance(points: &[Point3D], face: [usize; 3], point: Point3D) -> f64 {
+    let [a, b, c] = face.map(|v| points[v]);
+    let normal = cross(sub(b, a), sub(c, a));
+    dot(normal, sub(point, a)) / length(normal)
+}
+
+fn sub(a: Point3D, b: Point3D) -> Point3D {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: Point3D, b: Point3D) -> Point3D {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: Point3D, b: Point3D) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(a: Point3D) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// How a point relates to the territory it was matched to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerritoryMatch {
+    pub id: u32,
+    /// Whether the point lies inside the territory's convex hull
+    pub inside_hull: bool,
+    /// Closest member system to the point
+    pub nearest_system: SystemId,
+    /// Distance from the point to that system in meters
+    pub distance: f64,
+}
+
+/// Nearest systems whose territories are considered for a point
+const TERRITORY_CANDIDATES: usize = 32;
+
+impl SpatialIndex {
+    /// The region and constellation a point falls in.
+    ///
+    /// Candidate territories are those of the point's nearest systems from
+    /// the KD-tree, and their hulls are tested first. When none of them
+    /// contains the point, the hulls of every territory whose bounding box
+    /// contains it are tested as well, so a sparse territory wrapped around
+    /// a denser one is still found. Hulls of neighbouring territories may
+    /// overlap, so among the hulls containing the point the one with the
+    /// closest member system wins. A point outside every hull goes to the
+    /// territory of its nearest system, i.e. the Voronoi cell of the member
+    /// systems. The constellation is always chosen within the matched region.
+    pub fn locate_territory(&self, point: Point3D) -> (Option<TerritoryMatch>, Option<TerritoryMatch>) {
+        let nearest = self.find_nearest_systems(point, TERRITORY_CANDIDATES);
+
+        let region = self.closest_territory(
+            point,
+            &nearest,
+            |system| system.region_id,
+            &self.geometry.regions,
+            |id| self.regions.get(&id).map(|region| region.solar_system_ids.as_slice()),
+        );
+
+        let constellation = region.and_then(|region| {
+            self.closest_territory(
+                point,
+                &nearest,
+                |system| system.constellation_id.filter(|_| system.region_id == Some(region.id)),
+                &self.geometry.constellations,
+                |id| {
+                    self.constellations
+                        .get(&id)
+                        .map(|constellation| constellation.solar_system_ids.as_slice())
+                },
+            )
+        });
+
+        (region, constellation)
+    }
+
+    /// Best match among the territories of `nearest` systems: inside a hull
+    /// first, then by distance to the nearest member, then by lowest ID. If
+    /// no shortlisted hull contains the point, any other territory in
+    /// `geometries` whose hull does is preferred over them.
+    fn closest_territory<'a>(
+        &'a self,
+        point: Point3D,
+        nearest: &[(SystemId, f64)],
+        territory_of: impl Fn(&SolarSystem) -> Option<u32>,
+        geometries: &FxHashMap<u32, Geometry>,
+        members_of: impl Fn(u32) -> Option<&'a [SystemId]>,
+    ) -> Option<TerritoryMatch> {
+        // Closest shortlisted member of each candidate territory
+        let mut closest: FxHashMap<u32, (SystemId, f64)> = FxHashMap::default();
+        for &(system_id, distance) in nearest {
+            let Some(id) = self.systems.get(&system_id).and_then(&territory_of) else {
+                continue;
+            };
+            let entry = closest.entry(id).or_insert((system_id, distance));
+            if (distance, system_id) < (entry.1, entry.0) {
+                *entry = (system_id, distance);
+            }
+        }
+
+        let best = closest
+            .iter()
+            .map(|(&id, &(nearest_system, distance))| TerritoryMatch {
+                id,
+                inside_hull: geometries.get(&id).is_some_and(|geometry| self.hull_contains(geometry, point)),
+                nearest_system,
+                distance,
+            })
+            .min_by(|a, b| {
+                b.inside_hull
+                    .cmp(&a.inside_hull)
+                    .then_with(|| a.distance.total_cmp(&b.distance))
+                    .then_with(|| a.id.cmp(&b.id))
+            });
+        if best.is_some_and(|best| best.inside_hull) {
+            return best;
+        }
+
+        // A territory can enclose the point while all of its systems lie
+        // beyond the shortlist; hull_contains rejects points outside the
+        // bounding box before testing any faces
+        let enclosing = geometries
+            .iter()
+            .filter(|(id, geometry)| !closest.contains_key(id) && self.hull_contains(geometry, point))
+            .filter_map(|(&id, _)| {
+                let (distance, nearest_system) = members_of(id)?
+                    .iter()
+                    .filter_map(|system_id| self.systems.get(system_id).map(|system| (system_id, system)))
+                    .filter(|(_, system)| territory_of(system) == Some(id))
+                    .map(|(&system_id, system)| (length(sub(system.center, point)), system_id))
+                    .min_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)))?;
+                Some(TerritoryMatch {
+                    id,
+                    inside_hull: true,
+                    nearest_system,
+                    distance,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance).then_with(|| a.id.cmp(&b.id)));
+
+        enclosing.or(best)
+    }
+
+    /// Whether `point` lies inside (or on) a territory's hull; flat hulls
+    /// contain nothing
+    fn hull_contains(&self, geometry: &Geometry, point: Point3D) -> bool {
+        if geometry.hull.faces.is_empty() || (0..3).any(|i| point[i] < geometry.min[i] || point[i] > geometry.max[i]) {
+            return false;
+        }
+        let Some(positions) = geometry
+            .hull
+            .vertices
+            .iter()
+            .map(|id| self.systems.get(id).map(|system| system.center))
+            .collect::<Option<Vec<Point3D>>>()
+        else {
+            return false;
+        };
+
+        let tolerance = geometry.radius * 1e-9;
+        geometry
+            .hull
+            .faces
+            .iter()
+            .all(|face| plane_distance(&positions, face.map(|v| v as usize), point) <= tolerance)
+    }
+
+    pub fn region_geometry(&self, region_id: u32) -> Option<&Geometry> {
+        self.geometry.regions.get(&region_id)
+    }
+
+    pub fn constellation_geometry(&self, constellation_id: u32) -> Option<&Geometry> {
+        self.geometry.constellations.get(&constellation_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::system;
+    use super::super::SpatialIndex;
+    use super::{Geometry, TERRITORY_CANDIDATES};
+    use crate::coordinates::Coordinate3D;
+
+    fn ly(x: f64, y: f64, z: f64) -> [f64; 3] {
+        Coordinate3D::from_light_years(x, y, z).to_meters_array()
+    }
+
+    #[test]
+    fn test_cube_hull_skips_interior_points() {
+        let mut points = Vec::new();
+        for (i, corner) in (0..8).enumerate() {
+            let [x, y, z] = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1].map(|bit| bit as f64 * 2.0);
+            points.push((ly(x, y, z), i as u32 + 1));
+        }
+        // Centre and a face midpoint are not hull vertices
+        points.push((ly(1.0, 1.0, 1.0), 100));
+        points.push((ly(1.0, 1.0, 2.0), 101));
+
+        let geometry = Geometry::of(&points).unwrap();
+        assert_eq!(geometry.hull.vertices, (1..=8).collect::<Vec<u32>>());
+        assert_eq!(geometry.hull.faces.len(), 12);
+        assert_eq!(geometry.min, ly(0.0, 0.0, 0.0));
+        assert_eq!(geometry.max, ly(2.0, 2.0, 2.0));
+
+        // Every face points away from the centroid
+        let local: Vec<[f64; 3]> = geometry
+            .hull
+            .vertices
+            .iter()
+            .map(|&id| points.iter().find(|(_, pid)| *pid == id).unwrap().0)
+            .collect();
+        for face in &geometry.hull.faces {
+            let face = face.map(|v| v as usize);
+            assert!(super::plane_distance(&local, face, geometry.centroid) < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_flat_and_single_point_geometry() {
+        let flat = vec![
+            (ly(0.0, 0.0, 0.0), 1),
+            (ly(3.0, 0.0, 0.0), 2),
+            (ly(0.0, 4.0, 0.0), 3),
+        ];
+        let geometry = Geometry::of(&flat).unwrap();
+        assert!(geometry.hull.faces.is_empty());
+        assert_eq!(geometry.hull.vertices, vec![1, 2, 3]);
+
+        let single = Geometry::of(&[(ly(5.0, 5.0, 5.0), 7)]).unwrap();
+        assert_eq!(single.radius, 0.0);
+        assert_eq!(single.centroid, ly(5.0, 5.0, 5.0));
+        assert!(Geometry::of(&[]).is_none());
+    }
+
+    #[test]
+    fn test_locate_territory_prefers_hull_then_nearest_system() {
+        // Region 1 is a large tetrahedron, region 2 a small one inside its
+        // hull near one corner; each region is a single constellation
+        let mut systems = vec![
+            system(1, "A1", [0.0, 0.0, 0.0]),
+            system(2, "A2", [20.0, 0.0, 0.0]),
+            system(3, "A3", [0.0, 20.0, 0.0]),
+            system(4, "A4", [0.0, 0.0, 20.0]),
+            system(5, "B1", [2.0, 2.0, 2.0]),
+            system(6, "B2", [4.0, 2.0, 2.0]),
+            system(7, "B3", [2.0, 4.0, 2.0]),
+            system(8, "B4", [2.0, 2.0, 4.0]),
+        ];
+        for (i, (_, solar_system, _)) in systems.iter_mut().enumerate() {
+            let territory = if i < 4 { 1 } else { 2 };
+            solar_system.region_id = Some(territory);
+            solar_system.constellation_id = Some(10 + territory);
+        }
+        let index = SpatialIndex::from_parts(
+            systems,
+            vec![(1, "R1".to_string()), (2, "R2".to_string())],
+            vec![(11, "C1".to_string(), 1), (12, "C2".to_string(), 2)],
+            Vec::new(),
+        );
+
+        // Inside both hulls: region 2 owns the closer system
+        let (region, constellation) = index.locate_territory(ly(2.5, 2.5, 2.5));
+        let region = region.unwrap();
+        assert_eq!((region.id, region.inside_hull, region.nearest_system), (2, true, 5));
+        assert_eq!(constellation.unwrap().id, 12);
+
+        // Inside only region 1's hull, though closer to a region 2 system
+        let (region, _) = index.locate_territory(ly(6.0, 2.0, 2.0));
+        assert_eq!((region.unwrap().id, region.unwrap().inside_hull), (1, true));
+
+        // Outside every hull: nearest system decides
+        let (region, constellation) = index.locate_territory(ly(-1.0, 21.0, 0.0));
+        let region = region.unwrap();
+        assert_eq!((region.id, region.inside_hull, region.nearest_system), (1, false, 3));
+        assert!((region.distance / ly(1.0, 0.0, 0.0)[0] - 2f64.sqrt()).abs() < 1e-9);
+        assert_eq!(constellation.unwrap().id, 11);
+    }
+
+    #[test]
+    fn test_locate_territory_finds_hull_beyond_nearest_systems() {
+        // Region 1 is a sparse tetrahedron wrapped around region 2, a dense
+        // block with more systems than the KD-tree shortlist
+        let mut systems = vec![
+            system(1, "A1", [0.0, 0.0, 0.0]),
+            system(2, "A2", [100.0, 0.0, 0.0]),
+            system(3, "A3", [0.0, 100.0, 0.0]),
+            system(4, "A4", [0.0, 0.0, 100.0]),
+        ];
+        for i in 0..48u32 {
+            let [x, y, z] = [i % 4, (i / 4) % 4, i / 16].map(|step| 10.0 + step as f64 * 0.5);
+            systems.push(system(100 + i, "B", [x, y, z]));
+        }
+        for (id, solar_system, _) in systems.iter_mut() {
+            let territory = if *id < 100 { 1 } else { 2 };
+            solar_system.region_id = Some(territory);
+            solar_system.constellation_id = Some(10 + territory);
+        }
+        let index = SpatialIndex::from_parts(
+            systems,
+            vec![(1, "R1".to_string()), (2, "R2".to_string())],
+            vec![(11, "C1".to_string(), 1), (12, "C2".to_string(), 2)],
+            Vec::new(),
+        );
+
+        // Every shortlisted system belongs to region 2, but only region 1's
+        // hull contains the point
+        let point = ly(14.0, 10.5, 10.5);
+        assert!(index
+            .find_nearest_systems(point, TERRITORY_CANDIDATES)
+            .iter()
+            .all(|&(id, _)| id >= 100));
+        let (region, constellation) = index.locate_territory(point);
+        let region = region.unwrap();
+        assert_eq!((region.id, region.inside_hull, region.nearest_system), (1, true, 1));
+        let constellation = constellation.unwrap();
+        assert_eq!((constellation.id, constellation.inside_hull), (11, true));
+    }
+}
@@ -0,0 +1,84 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::SystemId;
+use crate::models::GateConnection;
+
+/// Kind of link between two systems, mirroring `gate_connections.connection_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConnectionKind {
+    Stargate,
+    JumpBridge,
+    Wormhole,
+}
+
+impl ConnectionKind {
+    /// Parse the value stored in the `connection_type` column
+    pub fn from_db_str(value: &str) -> Option<Self> {
+        match value {
+            "stargate" => Some(Self::Stargate),
+            "jump_bridge" => Some(Self::JumpBridge),
+            "wormhole" => Some(Self::Wormhole),
+            _ => None,
+        }
+    }
+}
+
+/// One directed half of a gate connection
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GateEdge {
+    pub to: SystemId,
+    pub kind: ConnectionKind,
+}
+
+/// Undirected adjacency list of the gate network built from `gate_connections`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GateGraph {
+    adjacency: FxHashMap<SystemId, Vec<GateEdge>>,
+    connection_count: usize,
+}
+
+impl GateGraph {
+    pub fn from_connections(connections: &[GateConnection]) -> Self {
+        let mut adjacency: FxHashMap<SystemId, Vec<GateEdge>> = FxHashMap::default();
+        let mut connection_count = 0;
+
+        for connection in connections {
+            let Some(kind) = ConnectionKind::from_db_str(&connection.connection_type) else {
+                warn!("Skipping connection {} with unknown type '{}'", connection.id, connection.connection_type);
+                continue;
+            };
+
+            // Self-loops carry no routing information
+            if connection.from_system_id == connection.to_system_id {
+                continue;
+            }
+
+            // Connections are stored once per pair, so add both directions
+            adjacency
+                .entry(connection.from_system_id)
+                .or_default()
+                .push(GateEdge { to: connection.to_system_id, kind });
+            adjacency
+                .entry(connection.to_system_id)
+                .or_default()
+                .push(GateEdge { to: connection.from_system_id, kind });
+            connection_count += 1;
+        }
+
+        Self { adjacency, connection_count }
+    }
+
+    /// Outgoing edges of a system (empty if it has no gates)
+    pub fn neighbours(&self, system_id: SystemId) -> &[GateEdge] {
+        self.adjacency
+            .get(&system_id)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.connection_count
+    }
+}
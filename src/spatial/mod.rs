@@ -7,16 +7,48 @@ use std::path::Path;
 use tokio::fs;
 use sha2::{Sha256, Digest};
 
-use crate::models::{SolarSystem, Region, Constellation, ConstellationMetadata};
+use crate::models::{CentralityMetric, SolarSystem, Region, Constellation, ConstellationMetadata, GateConnection, SystemCentrality, SystemInfo};
 use crate::database::Database;
 
+pub mod clustering;
+pub mod filters;
+pub mod geometry;
+pub mod graph;
+pub mod names;
+pub mod network;
+pub mod projection;
+pub mod rendezvous;
+pub mod routing;
+pub mod tiles;
+pub mod tour;
+pub mod volume;
+
+#[cfg(test)]
+mod test_fixtures;
+
+use geometry::TerritoryGeometry;
+use graph::GateGraph;
+use names::{NameMatch, NameSearch};
+use network::NetworkAnalysis;
+use projection::RegionLayout;
+use rstar::RTree;
+use tiles::TileSet;
+use volume::SystemPoint;
+
 pub type Point3D = [f64; 3];
 pub type SystemId = u32;
 
+/// Format of the binary cache. The cache holds derived results (network
+/// analysis, tiles, layouts, geometry) as well as raw data, so bump this
+/// whenever the layout or any of those algorithms changes; the data
+/// fingerprint alone cannot tell that the cached results are stale.
+const CACHE_FORMAT: u32 = 1;
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct SerializableSpatialData {
     // Data fingerprint for integrity and change detection
     data_fingerprint: String,
+    cache_format: u32,
     version: String,
     created_at: u64,
     
@@ -29,6 +61,11 @@ struct SerializableSpatialData {
     localized_names: FxHashMap<u32, String>,
     system_name_list: Vec<(String, SystemId)>,
     system_positions: Vec<(Point3D, SystemId)>,
+    gate_graph: GateGraph,
+    network: NetworkAnalysis,
+    tiles: TileSet,
+    layouts: FxHashMap<u32, RegionLayout>,
+    geometry: TerritoryGeometry,
 }
 
 #[derive(Debug)]
@@ -53,9 +90,32 @@ pub struct SpatialIndex {
     
     // Name mappings for autocomplete
     system_name_list: Vec<(String, SystemId)>,
+
+    // Typo-tolerant search over system, constellation and region names.
+    // Not serialized; rebuilt from the names above on load.
+    search: NameSearch,
     
     // Store system positions to map back from KdTree indices
     system_positions: Vec<(Point3D, SystemId)>,
+
+    // R-tree over the same positions for box and volume queries.
+    // Not serialized (rstar is built without serde); rebuilt on load.
+    rtree: RTree<SystemPoint>,
+
+    // Stargates, jump bridges and wormholes for route planning
+    gate_graph: GateGraph,
+
+    // Precomputed structure of the gate network
+    network: NetworkAnalysis,
+
+    // Level-of-detail octree tiles for map streaming
+    tiles: TileSet,
+
+    // Precomputed 2D layouts of each region
+    layouts: FxHashMap<u32, RegionLayout>,
+
+    // Centres, bounds and hulls of regions and constellations
+    geometry: TerritoryGeometry,
 }
 
 impl SpatialIndex {
@@ -95,15 +155,6 @@ impl SpatialIndex {
     }
 
     pub async fn load_from_database(database: &Database, data_dir: &str) -> Result<Self> {
-        let mut kdtree = KdTree::new();
-        let mut systems = FxHashMap::default();
-        let mut system_names = FxHashMap::default();
-        let mut regions = FxHashMap::default();
-        let mut constellations = FxHashMap::default();
-        let mut localized_names = FxHashMap::default();
-        let mut system_name_list = Vec::new();
-        let mut system_positions = Vec::new();
-
         // Check if database needs updating and seed if necessary
         if database.needs_update(data_dir).await? {
             info!("Database is empty or outdated, seeding from JSON files...");
@@ -116,6 +167,43 @@ impl SpatialIndex {
         let db_systems = database.load_all_systems().await?;
         info!("Loaded {} systems from database", db_systems.len());
 
+        // Load regions from database
+        let db_regions = database.load_all_regions().await?;
+        info!("Loaded {} regions from database", db_regions.len());
+
+        // Load constellations from database
+        let db_constellations = database.load_all_constellations().await?;
+        info!("Loaded {} constellations from database", db_constellations.len());
+
+        // Load gate connections from database
+        let db_connections = database.load_all_connections().await?;
+        info!("Loaded {} gate connections from database", db_connections.len());
+
+        let index = Self::from_parts(db_systems, db_regions, db_constellations, db_connections);
+
+        info!("Spatial index loaded successfully: {} systems, {} regions, {} constellations, {} connections", 
+              index.systems.len(), index.regions.len(), index.constellations.len(),
+              index.gate_graph.connection_count());
+
+        Ok(index)
+    }
+
+    /// Build the index from rows as returned by the `Database::load_all_*` methods
+    pub fn from_parts(
+        db_systems: Vec<(SystemId, SolarSystem, String)>,
+        db_regions: Vec<(u32, String)>,
+        db_constellations: Vec<(u32, String, u32)>,
+        db_connections: Vec<GateConnection>,
+    ) -> Self {
+        let mut kdtree = KdTree::new();
+        let mut systems = FxHashMap::default();
+        let mut system_names = FxHashMap::default();
+        let mut regions = FxHashMap::default();
+        let mut constellations = FxHashMap::default();
+        let mut localized_names = FxHashMap::default();
+        let mut system_name_list = Vec::new();
+        let mut system_positions = Vec::new();
+
         for (system_id, system, name) in db_systems {
             // Add to KD-tree
             kdtree.add(&system.center, system_positions.len());
@@ -128,33 +216,25 @@ impl SpatialIndex {
             system_name_list.push((name, system_id));
         }
 
-        // Load regions from database
-        let db_regions = database.load_all_regions().await?;
-        info!("Loaded {} regions from database", db_regions.len());
-
         for (region_id, region_name) in db_regions {
-            // Create a minimal region object - we might need to expand this
+            // Members and center are filled in from the systems below
             let region = Region {
-                solar_system_ids: Vec::new(), // We don't store this in DB currently
+                solar_system_ids: Vec::new(),
                 neighbours: Vec::new(),       // We don't store this in DB currently
-                center: [0.0, 0.0, 0.0],     // We don't store this in DB currently
-                constellation_ids: Vec::new(), // We don't store this in DB currently
+                center: [0.0, 0.0, 0.0],
+                constellation_ids: Vec::new(),
             };
             regions.insert(region_id, region);
             localized_names.insert(region_id, region_name);
         }
 
-        // Load constellations from database
-        let db_constellations = database.load_all_constellations().await?;
-        info!("Loaded {} constellations from database", db_constellations.len());
-
         for (constellation_id, constellation_name, region_id) in db_constellations {
-            // Create a minimal constellation object
+            // Members are filled in from the systems below
             let constellation = Constellation {
                 id: constellation_id,
                 name: constellation_name.clone(),
                 region_id,
-                solar_system_ids: Vec::new(), // We don't store this in DB currently
+                solar_system_ids: Vec::new(),
                 metadata: ConstellationMetadata {
                     faction_id: None,
                     sovereignty: None,
@@ -167,10 +247,15 @@ impl SpatialIndex {
         // Sort system names for better autocomplete performance
         system_name_list.sort_by(|a, b| a.0.cmp(&b.0));
 
-        info!("Spatial index loaded successfully: {} systems, {} regions, {} constellations", 
-              systems.len(), regions.len(), constellations.len());
+        let gate_graph = GateGraph::from_connections(&db_connections);
+        let network = NetworkAnalysis::compute(&gate_graph, &systems);
+        let rtree = volume::build_rtree(&system_positions);
+        let tiles = TileSet::build(&system_positions);
+        let layouts = projection::compute_region_layouts(&gate_graph, &systems);
+        let geometry = TerritoryGeometry::compute(&systems, &mut regions, &mut constellations);
+        let search = NameSearch::build(&system_name_list, &regions, &constellations, &localized_names);
 
-        Ok(Self {
+        let mut index = Self {
             kdtree,
             systems,
             system_names,
@@ -178,12 +263,19 @@ impl SpatialIndex {
             constellations,
             localized_names,
             system_name_list,
+            search,
             system_positions,
-        })
+            rtree,
+            gate_graph,
+            network,
+            tiles,
+            layouts,
+            geometry,
+        };
+        index.find_component_entries();
+        index
     }
 
-
-
     pub fn get_system_name(&self, id: SystemId) -> Option<&String> {
         self.localized_names.get(&id)
     }
@@ -218,28 +310,135 @@ impl SpatialIndex {
         self.system_names.get(name).copied()
     }
 
+    /// Resolve a system given either its exact name or its numeric ID
+    pub fn resolve_system(&self, name_or_id: &str) -> Option<SystemId> {
+        self.find_system_by_name(name_or_id).or_else(|| {
+            name_or_id
+                .trim()
+                .parse::<SystemId>()
+                .ok()
+                .filter(|id| self.systems.contains_key(id))
+        })
+    }
+
+    /// Build the API representation of a system, with an optional distance in light-years
+    pub fn system_info(&self, id: SystemId, distance: Option<f64>) -> Option<SystemInfo> {
+        let system = self.systems.get(&id)?;
+        Some(SystemInfo {
+            id,
+            name: self.get_system_name(id).cloned(),
+            center: system.center,
+            region_id: system.region_id,
+            constellation_id: system.constellation_id,
+            faction_id: system.metadata.faction_id,
+            distance,
+            centrality: self.system_centrality(id),
+        })
+    }
+
+    pub fn system_centrality(&self, id: SystemId) -> Option<SystemCentrality> {
+        self.network.centrality.get(&id).copied()
+    }
+
+    /// Systems ranked by descending centrality, optionally restricted to one region
+    pub fn rank_hubs(&self, region_id: Option<u32>, metric: CentralityMetric) -> Vec<(SystemId, SystemCentrality)> {
+        let score = |centrality: &SystemCentrality| match metric {
+            CentralityMetric::Betweenness => centrality.betweenness,
+            CentralityMetric::Closeness => centrality.closeness,
+        };
+
+        let mut ranked: Vec<(SystemId, SystemCentrality)> = self
+            .network
+            .centrality
+            .iter()
+            .filter(|(id, _)| {
+                region_id.is_none() || self.systems.get(id).and_then(|s| s.region_id) == region_id
+            })
+            .map(|(&id, &centrality)| (id, centrality))
+            .collect();
+        ranked.sort_by(|a, b| score(&b.1).total_cmp(&score(&a.1)).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
     pub fn get_system(&self, id: SystemId) -> Option<&SolarSystem> {
         self.systems.get(&id)
     }
 
-    #[allow(dead_code)]
     pub fn get_region(&self, id: u32) -> Option<&Region> {
         self.regions.get(&id)
     }
 
-    #[allow(dead_code)]
+    pub fn get_region_name(&self, id: u32) -> Option<&String> {
+        self.regions.contains_key(&id).then(|| self.localized_names.get(&id)).flatten()
+    }
+
     pub fn get_constellation(&self, id: u32) -> Option<&Constellation> {
         self.constellations.get(&id)
     }
 
-    pub fn autocomplete_systems(&self, query: &str, limit: usize) -> Vec<(String, SystemId)> {
-        let query_lower = query.to_lowercase();
-        self.system_name_list
-            .iter()
-            .filter(|(name, _)| name.to_lowercase().contains(&query_lower))
-            .take(limit)
-            .cloned()
-            .collect()
+    pub fn get_constellation_name(&self, id: u32) -> Option<&String> {
+        let constellation = self.constellations.get(&id)?;
+        Some(self.localized_names.get(&id).unwrap_or(&constellation.name))
+    }
+
+    /// System names matching `query`, best match first (prefix, then
+    /// substring, then within a typo or two), optionally only those inside
+    /// a region and/or constellation
+    pub fn autocomplete_systems(
+        &self,
+        query: &str,
+        limit: usize,
+        region_id: Option<u32>,
+        constellation_id: Option<u32>,
+    ) -> Vec<NameMatch> {
+        self.search.systems.search(query, limit, |id| {
+            self.systems.get(&id).is_some_and(|system| {
+                region_id.is_none_or(|region| system.region_id == Some(region))
+                    && constellation_id.is_none_or(|constellation| system.constellation_id == Some(constellation))
+            })
+        })
+    }
+
+    /// Constellation names matching `query`, ranked like `autocomplete_systems`
+    pub fn autocomplete_constellations(
+        &self,
+        query: &str,
+        limit: usize,
+        region_id: Option<u32>,
+        constellation_id: Option<u32>,
+    ) -> Vec<NameMatch> {
+        self.search.constellations.search(query, limit, |id| {
+            self.constellations.get(&id).is_some_and(|constellation| {
+                region_id.is_none_or(|region| constellation.region_id == region)
+                    && constellation_id.is_none_or(|only| id == only)
+            })
+        })
+    }
+
+    /// Region names matching `query`, ranked like `autocomplete_systems`
+    pub fn autocomplete_regions(
+        &self,
+        query: &str,
+        limit: usize,
+        region_id: Option<u32>,
+        constellation_id: Option<u32>,
+    ) -> Vec<NameMatch> {
+        let parent = constellation_id.map(|id| self.constellations.get(&id).map(|c| c.region_id));
+        self.search.regions.search(query, limit, |id| {
+            region_id.is_none_or(|only| id == only) && parent.is_none_or(|parent| parent == Some(id))
+        })
+    }
+
+    pub fn network_analysis(&self) -> &NetworkAnalysis {
+        &self.network
+    }
+
+    pub fn map_tiles(&self) -> &TileSet {
+        &self.tiles
+    }
+
+    pub fn region_layout(&self, region_id: u32) -> Option<&RegionLayout> {
+        self.layouts.get(&region_id)
     }
 
     pub fn system_count(&self) -> usize {
@@ -260,6 +459,7 @@ impl SpatialIndex {
         
         let serializable_data = SerializableSpatialData {
             data_fingerprint,
+            cache_format: CACHE_FORMAT,
             version: env!("CARGO_PKG_VERSION").to_string(),
             created_at: now,
             kdtree: self.kdtree.clone(),
@@ -270,6 +470,11 @@ impl SpatialIndex {
             localized_names: self.localized_names.clone(),
             system_name_list: self.system_name_list.clone(),
             system_positions: self.system_positions.clone(),
+            gate_graph: self.gate_graph.clone(),
+            network: self.network.clone(),
+            tiles: self.tiles.clone(),
+            layouts: self.layouts.clone(),
+            geometry: self.geometry.clone(),
         };
 
         let binary_data = bincode::serialize(&serializable_data)?;
@@ -292,6 +497,13 @@ impl SpatialIndex {
         let binary_data = fs::read(file_path).await?;
         let serializable_data: SerializableSpatialData = bincode::deserialize(&binary_data)?;
         
+        // Results computed by a different build may be stale even for the same data
+        if serializable_data.cache_format != CACHE_FORMAT || serializable_data.version != env!("CARGO_PKG_VERSION") {
+            return Err(anyhow::anyhow!("Cache format mismatch. Cache is outdated (cached: {} v{}, current: {} v{})",
+                      serializable_data.cache_format, serializable_data.version,
+                      CACHE_FORMAT, env!("CARGO_PKG_VERSION")));
+        }
+
         // Verify data fingerprint
         let current_fingerprint = Self::compute_data_fingerprint(data_dir).await?;
         if serializable_data.data_fingerprint != current_fingerprint {
@@ -306,6 +518,14 @@ impl SpatialIndex {
               serializable_data.version,
               serializable_data.created_at);
 
+        let rtree = volume::build_rtree(&serializable_data.system_positions);
+        let search = NameSearch::build(
+            &serializable_data.system_name_list,
+            &serializable_data.regions,
+            &serializable_data.constellations,
+            &serializable_data.localized_names,
+        );
+
         Ok(Self {
             kdtree: serializable_data.kdtree,
             systems: serializable_data.systems,
@@ -314,7 +534,14 @@ impl SpatialIndex {
             constellations: serializable_data.constellations,
             localized_names: serializable_data.localized_names,
             system_name_list: serializable_data.system_name_list,
+            search,
             system_positions: serializable_data.system_positions,
+            rtree,
+            gate_graph: serializable_data.gate_graph,
+            network: serializable_data.network,
+            tiles: serializable_data.tiles,
+            layouts: serializable_data.layouts,
+            geometry: serializable_data.geometry,
         })
     }
 
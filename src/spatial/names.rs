@@ -0,0 +1,337 @@
+use rustc_hash::FxHashMap;
+
+use super::SystemId;
+use crate::models::{Constellation, Region};
+
+/// Longest query, in characters, that name searches accept; no name comes
+/// close, and every candidate costs time proportional to the query length
+pub const MAX_QUERY_CHARS: usize = 64;
+
+/// One indexed name
+#[derive(Debug, Clone)]
+struct NameEntry {
+    /// Lowercase letters and digits of the name, punctuation and spaces dropped
+    key: Vec<char>,
+    name: String,
+    id: u32,
+}
+
+/// A name matched by `NameIndex::search`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameMatch {
+    pub id: u32,
+    pub name: String,
+    /// Match quality in 0..=1; 1 is an exact match
+    pub score: f64,
+    /// Typos corrected to reach the match (0 for exact, prefix and substring matches)
+    pub edits: usize,
+}
+
+/// Typo-tolerant name lookup for autocomplete.
+///
+/// Names are compared on their letters and digits only, case-insensitively,
+/// so "e1s2t" matches "E1S-2T3". Matches rank prefix first, then substring,
+/// then names whose beginning is within a small edit distance of the query.
+/// Candidates come from a bigram index rather than a scan of every name.
+#[derive(Debug, Clone, Default)]
+pub struct NameIndex {
+    /// Sorted by key so prefix matches form a contiguous range
+    entries: Vec<NameEntry>,
+    bigrams: FxHashMap<(char, char), Vec<u32>>,
+}
+
+/// Name indexes for each level of the territory hierarchy
+#[derive(Debug, Clone, Default)]
+pub struct NameSearch {
+    pub systems: NameIndex,
+    pub constellations: NameIndex,
+    pub regions: NameIndex,
+}
+
+impl NameSearch {
+    pub fn build(
+        system_names: &[(String, SystemId)],
+        regions: &FxHashMap<u32, Region>,
+        constellations: &FxHashMap<u32, Constellation>,
+        localized_names: &FxHashMap<u32, String>,
+    ) -> Self {
+        Self {
+            systems: NameIndex::build(system_names.iter().cloned()),
+            constellations: NameIndex::build(constellations.iter().map(|(&id, constellation)| {
+                let name = localized_names.get(&id).unwrap_or(&constellation.name);
+                (name.clone(), id)
+            })),
+            regions: NameIndex::build(
+                regions
+                    .keys()
+                    .filter_map(|&id| localized_names.get(&id).map(|name| (name.clone(), id))),
+            ),
+        }
+    }
+}
+
+impl NameIndex {
+    pub fn build(names: impl IntoIterator<Item = (String, u32)>) -> Self {
+        let mut entries: Vec<NameEntry> = names
+            .into_iter()
+            .map(|(name, id)| NameEntry { key: normalize(&name), name, id })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.name.cmp(&b.name)).then(a.id.cmp(&b.id)));
+
+        let mut bigrams: FxHashMap<(char, char), Vec<u32>> = FxHashMap::default();
+        for (i, entry) in entries.iter().enumerate() {
+            for pair in entry.key.windows(2) {
+                let postings = bigrams.entry((pair[0], pair[1])).or_default();
+                // Repeated bigrams within one key are listed once
+                if postings.last() != Some(&(i as u32)) {
+                    postings.push(i as u32);
+                }
+            }
+        }
+
+        Self { entries, bigrams }
+    }
+
+    /// Best matches for `query`, highest score first. Ties go to the shorter
+    /// name, then alphabetical order. `accept` filters entries by ID before
+    /// they are scored.
+    pub fn search(&self, query: &str, limit: usize, accept: impl Fn(u32) -> bool) -> Vec<NameMatch> {
+        let query = normalize(query);
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let max_edits = allowed_edits(query.len());
+
+        // Names starting with the same character cover prefix matches and
+        // typos after the first character; shared bigrams cover the rest
+        let mut candidates: Vec<u32> = self.prefix_range(&query[..1]).map(|i| i as u32).collect();
+        for pair in query.windows(2) {
+            if let Some(postings) = self.bigrams.get(&(pair[0], pair[1])) {
+                candidates.extend_from_slice(postings);
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut matches: Vec<NameMatch> = candidates
+            .into_iter()
+            .filter_map(|i| {
+                let entry = &self.entries[i as usize];
+                if !accept(entry.id) {
+                    return None;
+                }
+                let (score, edits) = score(&query, &entry.key, max_edits)?;
+                Some(NameMatch {
+                    id: entry.id,
+                    name: entry.name.clone(),
+                    score,
+                    edits,
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.name.cmp(&b.name))
+                .then(a.id.cmp(&b.id))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Entries whose key starts with `prefix`
+    fn prefix_range(&self, prefix: &[char]) -> std::ops::Range<usize> {
+        let start = self.entries.partition_point(|entry| entry.key.as_slice() < prefix);
+        let end = start + self.entries[start..].partition_point(|entry| entry.key.starts_with(prefix));
+        start..end
+    }
+}
+
+/// Lowercase letters and digits of `text`
+fn normalize(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Typos tolerated for a query of `length` characters
+fn allowed_edits(length: usize) -> usize {
+    match length {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Score `key` against `query`, or `None` when it does not match.
+///
+/// Tiers never overlap: prefix matches score 0.7..=1, substring matches
+/// 0.4..0.6 and typo-corrected prefixes below 0.4. Within a tier, names
+/// closer in length to the query score higher.
+fn score(query: &[char], key: &[char], max_edits: usize) -> Option<(f64, usize)> {
+    // Too long to match even with every allowed typo
+    if query.len() > key.len() + max_edits {
+        return None;
+    }
+    let coverage = query.len() as f64 / key.len().max(query.len()) as f64;
+
+    if key.starts_with(query) {
+        return Some((0.7 + 0.3 * coverage, 0));
+    }
+    if key.windows(query.len()).any(|window| window == query) {
+        return Some((0.4 + 0.2 * coverage, 0));
+    }
+
+    let edits = prefix_edit_distance(query, key, max_edits)?;
+    if edits == 0 {
+        return None;
+    }
+    let closeness = 1.0 - edits as f64 / (max_edits + 1) as f64;
+    Some((0.3 * closeness + 0.1 * coverage, edits))
+}
+
+/// Fewest insertions, deletions, substitutions or adjacent transpositions
+/// turning `query` into some prefix of `key`, or `None` when that is more
+/// than `max_edits`
+fn prefix_edit_distance(query: &[char], key: &[char], max_edits: usize) -> Option<usize> {
+    let columns = key.len() + 1;
+    // Row i holds the distances between query[..i] and each key[..j]; a
+    // transposition looks back two rows, so three are kept
+    let mut before: Vec<usize> = vec![0; columns];
+    let mut previous: Vec<usize> = (0..columns).collect();
+    let mut row: Vec<usize> = vec![0; columns];
+    for i in 1..=query.len() {
+        row[0] = i;
+        for j in 1..columns {
+            let substitution = usize::from(query[i - 1] != key[j - 1]);
+            row[j] = (previous[j] + 1)
+                .min(row[j - 1] + 1)
+                .min(previous[j - 1] + substitution);
+            if i > 1 && j > 1 && query[i - 1] == key[j - 2] && query[i - 2] == key[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        // The smallest distance in a row never drops in later rows, so once
+        // it passes the limit the search can stop
+        if row.iter().all(|&distance| distance > max_edits) {
+            return None;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut row);
+    }
+    previous.iter().copied().min().filter(|&distance| distance <= max_edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::system;
+    use super::super::SpatialIndex;
+    use super::{prefix_edit_distance, NameIndex};
+
+    fn index() -> NameIndex {
+        NameIndex::build(
+            [
+                ("E1S-2T3", 1),
+                ("E1S-2TX", 2),
+                ("ZE1S-2T3", 3),
+                ("E1R-9QQ", 4),
+                ("Jita", 5),
+                ("Jitaville", 6),
+                ("Old Jita", 7),
+                ("HGE-U8N", 8),
+            ]
+            .map(|(name, id)| (name.to_string(), id)),
+        )
+    }
+
+    fn ids(matches: &[super::NameMatch]) -> Vec<u32> {
+        matches.iter().map(|m| m.id).collect()
+    }
+
+    #[test]
+    fn test_search_ranks_prefix_then_substring_then_typos() {
+        let index = index();
+
+        // Punctuation and case are ignored; the substring hit trails the prefix hits
+        let matches = index.search("e1s2t", 10, |_| true);
+        assert_eq!(ids(&matches), vec![1, 2, 3]);
+        assert!(matches[0].score > matches[2].score);
+        assert!(matches.iter().all(|m| m.edits == 0));
+
+        // Exact matches score 1 and shorter names win among prefixes
+        let matches = index.search("jita", 10, |_| true);
+        assert_eq!(ids(&matches), vec![5, 6, 7]);
+        assert_eq!(matches[0].score, 1.0);
+
+        // A substituted character is tolerated, ranked below exact hits
+        let matches = index.search("e1s3t", 10, |_| true);
+        assert_eq!(ids(&matches[..2]), vec![1, 2]);
+        assert_eq!(matches[0].edits, 1);
+        assert!(matches[0].score < 0.4);
+
+        // Transposed characters count as one typo
+        let matches = index.search("hge8u", 10, |_| true);
+        assert_eq!(ids(&matches), vec![8]);
+
+        // Short queries must match exactly
+        assert_eq!(ids(&index.search("hx", 10, |_| true)), Vec::<u32>::new());
+        assert!(index.search("--", 10, |_| true).is_empty());
+
+        // Filters and limits apply before results are returned
+        assert_eq!(ids(&index.search("jita", 10, |id| id != 5)), vec![6, 7]);
+        assert_eq!(ids(&index.search("jita", 1, |_| true)), vec![5]);
+    }
+
+    #[test]
+    fn test_autocomplete_filters_by_territory() {
+        let mut systems = vec![
+            system(1, "Aster-1", [0.0, 0.0, 0.0]),
+            system(2, "Aster-2", [1.0, 0.0, 0.0]),
+            system(3, "Aster-3", [2.0, 0.0, 0.0]),
+        ];
+        for (i, (_, solar_system, _)) in systems.iter_mut().enumerate() {
+            solar_system.region_id = Some(if i < 2 { 100 } else { 200 });
+            solar_system.constellation_id = Some(10 + i as u32);
+        }
+        let index = SpatialIndex::from_parts(
+            systems,
+            vec![(100, "Asteria".to_string()), (200, "Borealis".to_string())],
+            vec![
+                (10, "Aster Reach".to_string(), 100),
+                (11, "Aster Deep".to_string(), 100),
+                (12, "Aster Rim".to_string(), 200),
+            ],
+            Vec::new(),
+        );
+        let ids = |matches: Vec<super::NameMatch>| matches.into_iter().map(|m| m.id).collect::<Vec<u32>>();
+
+        assert_eq!(ids(index.autocomplete_systems("aster", 10, None, None)), vec![1, 2, 3]);
+        assert_eq!(ids(index.autocomplete_systems("aster", 10, Some(100), None)), vec![1, 2]);
+        assert_eq!(ids(index.autocomplete_systems("aster", 10, None, Some(12))), vec![3]);
+        assert_eq!(ids(index.autocomplete_systems("aster", 10, Some(100), Some(12))), Vec::<u32>::new());
+
+        assert_eq!(ids(index.autocomplete_constellations("aster", 10, None, None)), vec![12, 11, 10]);
+        assert_eq!(ids(index.autocomplete_constellations("aster", 10, Some(200), None)), vec![12]);
+        assert_eq!(ids(index.autocomplete_regions("aster", 10, None, None)), vec![100]);
+        assert_eq!(ids(index.autocomplete_regions("bor", 10, None, Some(12))), vec![200]);
+        assert_eq!(ids(index.autocomplete_regions("bor", 10, None, Some(10))), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_prefix_edit_distance() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        assert_eq!(prefix_edit_distance(&chars("abc"), &chars("abcdef"), 2), Some(0));
+        assert_eq!(prefix_edit_distance(&chars("abx"), &chars("abcdef"), 2), Some(1));
+        assert_eq!(prefix_edit_distance(&chars("bac"), &chars("abcdef"), 2), Some(1));
+        assert_eq!(prefix_edit_distance(&chars("acd"), &chars("abcdef"), 2), Some(1));
+        assert_eq!(prefix_edit_distance(&chars("xyz"), &chars("abc"), 3), Some(3));
+        // Beyond the limit the search gives up
+        assert_eq!(prefix_edit_distance(&chars("xyz"), &chars("abc"), 2), None);
+        assert_eq!(prefix_edit_distance(&chars("xbcdef"), &chars("abcdef"), 0), None);
+        // A transposition late in the query is still found after a costly row
+        assert_eq!(prefix_edit_distance(&chars("abdc"), &chars("abcd"), 1), Some(1));
+    }
+}
@@ -0,0 +1,564 @@
+use kiddo::float::kdtree::KdTree;
+use kiddo::SquaredEuclidean;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Instant;
+use tracing::info;
+
+use super::graph::GateGraph;
+use super::{Point3D, SpatialIndex, SystemId};
+use crate::models::{SolarSystem, SystemCentrality};
+
+/// Systems and gates whose loss splits the gate network
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chokepoints {
+    /// Systems whose removal disconnects their part of the network, sorted by ID
+    pub articulation_points: Vec<SystemId>,
+    /// Connections whose removal disconnects their part of the network, as sorted `(lower, higher)` ID pairs
+    pub bridges: Vec<(SystemId, SystemId)>,
+}
+
+/// A set of systems linked to each other by gates but not to the rest of the network
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GateComponent {
+    /// Member systems sorted by ID; a gate-less system forms a component on its own
+    pub systems: Vec<SystemId>,
+    /// Regions the members belong to, sorted
+    pub regions: Vec<u32>,
+    /// Shortest jump from the main component into this one (`None` for the main component)
+    pub entry: Option<JumpEntry>,
+}
+
+/// The closest pair of systems between the main component and another one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JumpEntry {
+    /// System in the main component to jump from
+    pub from: SystemId,
+    /// System in the other component to jump to
+    pub to: SystemId,
+    /// Straight-line distance in meters
+    pub distance: f64,
+}
+
+/// Structural analysis of the gate network, computed once when the index is built
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkAnalysis {
+    /// Chokepoints of the whole network
+    pub global: Chokepoints,
+    /// Chokepoints of each region's internal network, ignoring gates that leave the region
+    pub regions: FxHashMap<u32, Chokepoints>,
+    /// Centrality of every system on the whole network
+    pub centrality: FxHashMap<SystemId, SystemCentrality>,
+    /// Connected components, largest (the main component) first
+    pub components: Vec<GateComponent>,
+}
+
+impl NetworkAnalysis {
+    pub fn compute(graph: &GateGraph, systems: &FxHashMap<SystemId, SolarSystem>) -> Self {
+        let started = Instant::now();
+        let mut all: Vec<SystemId> = systems.keys().copied().collect();
+        all.sort_unstable();
+
+        let mut by_region: FxHashMap<u32, Vec<SystemId>> = FxHashMap::default();
+        for &id in &all {
+            if let Some(region_id) = systems[&id].region_id {
+                by_region.entry(region_id).or_default().push(id);
+            }
+        }
+
+        let regions = by_region
+            .into_iter()
+            .map(|(region_id, members)| (region_id, find_chokepoints(graph, &members)))
+            .collect();
+
+        let centrality = all
+            .iter()
+            .copied()
+            .zip(compute_centrality(graph, &all))
+            .collect();
+
+        let components = find_components(graph, &all)
+            .into_iter()
+            .map(|members| {
+                let mut regions: Vec<u32> = members.iter().filter_map(|id| systems[id].region_id).collect();
+                regions.sort_unstable();
+                regions.dedup();
+                GateComponent {
+                    systems: members,
+                    regions,
+                    entry: None,
+                }
+            })
+            .collect();
+
+        let analysis = Self {
+            global: find_chokepoints(graph, &all),
+            regions,
+            centrality,
+            components,
+        };
+        info!(
+            "Analysed gate network of {} systems in {:.2?}: {} components, {} articulation points, {} bridges",
+            all.len(),
+            started.elapsed(),
+            analysis.components.len(),
+            analysis.global.articulation_points.len(),
+            analysis.global.bridges.len()
+        );
+        analysis
+    }
+}
+
+impl SpatialIndex {
+    /// Fill in the jump entry point of every component other than the main one.
+    ///
+    /// A KD-tree over the main component's systems answers one nearest query
+    /// per member of every other component; the closest such pair is the entry.
+    pub(super) fn find_component_entries(&mut self) {
+        let Some(main) = self.network.components.first() else {
+            return;
+        };
+        let main_systems: Vec<(SystemId, Point3D)> = main
+            .systems
+            .iter()
+            .filter_map(|&id| Some((id, self.systems.get(&id)?.center)))
+            .collect();
+        if main_systems.is_empty() {
+            return;
+        }
+        let mut main_tree: KdTree<f64, usize, 3, 32, u32> = KdTree::new();
+        for (i, (_, center)) in main_systems.iter().enumerate() {
+            main_tree.add(center, i);
+        }
+
+        let entries: Vec<Option<JumpEntry>> = self.network.components[1..]
+            .iter()
+            .map(|component| {
+                component
+                    .systems
+                    .iter()
+                    .filter_map(|&id| {
+                        let center = self.systems.get(&id)?.center;
+                        let nearest = main_tree.nearest_one::<SquaredEuclidean>(&center);
+                        Some(JumpEntry {
+                            from: main_systems[nearest.item].0,
+                            to: id,
+                            distance: nearest.distance.sqrt(),
+                        })
+                    })
+                    .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            })
+            .collect();
+
+        for (component, entry) in self.network.components[1..].iter_mut().zip(entries) {
+            component.entry = entry;
+        }
+    }
+}
+
+/// Connected components of the subgraph induced by `members`, largest first.
+///
+/// Members are sorted within each component; ties in size go to the component
+/// with the lowest system ID.
+pub fn find_components(graph: &GateGraph, members: &[SystemId]) -> Vec<Vec<SystemId>> {
+    let member_set: FxHashSet<SystemId> = members.iter().copied().collect();
+    let mut seen: FxHashSet<SystemId> = FxHashSet::default();
+    let mut components = Vec::new();
+
+    for &start in members {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            for edge in graph.neighbours(current) {
+                if member_set.contains(&edge.to) && seen.insert(edge.to) {
+                    component.push(edge.to);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+    components
+}
+
+/// Articulation points and bridges of the subgraph induced by `members`.
+///
+/// Iterative Tarjan low-link search, so deep gate chains cannot overflow the
+/// stack. Parallel connections between the same pair of systems are kept, so
+/// a pair joined twice is never reported as a bridge.
+pub fn find_chokepoints(graph: &GateGraph, members: &[SystemId]) -> Chokepoints {
+    let slot: FxHashMap<SystemId, usize> = members.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let adjacency: Vec<Vec<usize>> = members
+        .iter()
+        .map(|&id| {
+            graph
+                .neighbours(id)
+                .iter()
+                .filter_map(|edge| slot.get(&edge.to).copied())
+                .collect()
+        })
+        .collect();
+
+    let n = members.len();
+    let mut discovered = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut parent: Vec<Option<usize>> = vec![None; n];
+    let mut is_articulation = vec![false; n];
+    let mut bridges = Vec::new();
+    let mut timer = 0;
+
+    for root in 0..n {
+        if discovered[root] != usize::MAX {
+            continue;
+        }
+
+        discovered[root] = timer;
+        low[root] = timer;
+        timer += 1;
+        let mut root_children = 0;
+        // (node, next adjacency index, whether the edge back to the parent has been skipped)
+        let mut stack = vec![(root, 0, false)];
+
+        while let Some((node, next_edge, skipped_parent)) = stack.last_mut() {
+            let node = *node;
+            if let Some(&next) = adjacency[node].get(*next_edge) {
+                *next_edge += 1;
+
+                // Skip exactly one copy of the tree edge we arrived by
+                if Some(next) == parent[node] && !*skipped_parent {
+                    *skipped_parent = true;
+                    continue;
+                }
+
+                if discovered[next] == usize::MAX {
+                    parent[next] = Some(node);
+                    discovered[next] = timer;
+                    low[next] = timer;
+                    timer += 1;
+                    if node == root {
+                        root_children += 1;
+                    }
+                    stack.push((next, 0, false));
+                } else {
+                    low[node] = low[node].min(discovered[next]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            if let Some(up) = parent[node] {
+                low[up] = low[up].min(low[node]);
+                if low[node] > discovered[up] {
+                    let (a, b) = (members[up], members[node]);
+                    bridges.push((a.min(b), a.max(b)));
+                }
+                if parent[up].is_some() && low[node] >= discovered[up] {
+                    is_articulation[up] = true;
+                }
+            }
+        }
+
+        if root_children > 1 {
+            is_articulation[root] = true;
+        }
+    }
+
+    let mut articulation_points: Vec<SystemId> = (0..n)
+        .filter(|&i| is_articulation[i])
+        .map(|i| members[i])
+        .collect();
+    articulation_points.sort_unstable();
+    bridges.sort_unstable();
+
+    Chokepoints {
+        articulation_points,
+        bridges,
+    }
+}
+
+/// Normalised betweenness and closeness centrality of every member, in `members` order.
+///
+/// Brandes' algorithm over the unweighted gate graph, with one breadth-first
+/// search per source split across the available cores. Betweenness is the
+/// share of all shortest paths between other systems that pass through a
+/// system (0 to 1). Closeness uses the Wasserman-Faust form, which scales by
+/// the fraction of the network a system can reach so that small isolated
+/// pockets do not score as central.
+pub fn compute_centrality(graph: &GateGraph, members: &[SystemId]) -> Vec<SystemCentrality> {
+    let n = members.len();
+    let slot: FxHashMap<SystemId, usize> = members.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let adjacency: Vec<Vec<usize>> = members
+        .iter()
+        .map(|&id| {
+            // Parallel connections would double-count shortest paths
+            let mut neighbours: Vec<usize> = graph
+                .neighbours(id)
+                .iter()
+                .filter_map(|edge| slot.get(&edge.to).copied())
+                .collect();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+            neighbours
+        })
+        .collect();
+
+    let threads = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .clamp(1, n.max(1));
+
+    // Each worker fills closeness for its own sources only, so partials simply add up
+    let partials: Vec<(Vec<f64>, Vec<f64>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|worker| {
+                let adjacency = &adjacency;
+                scope.spawn(move || {
+                    let mut betweenness = vec![0.0; n];
+                    let mut closeness = vec![0.0; n];
+                    let mut search = BrandesSearch::new(n);
+                    for source in (worker..n).step_by(threads) {
+                        closeness[source] = search.run(adjacency, source, &mut betweenness);
+                    }
+                    (betweenness, closeness)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("centrality worker panicked"))
+            .collect()
+    });
+
+    let mut betweenness = vec![0.0; n];
+    let mut closeness = vec![0.0; n];
+    for (partial_betweenness, partial_closeness) in partials {
+        for (total, value) in betweenness.iter_mut().zip(partial_betweenness) {
+            *total += value;
+        }
+        for (total, value) in closeness.iter_mut().zip(partial_closeness) {
+            *total += value;
+        }
+    }
+
+    // Each unordered pair is counted from both ends
+    let scale = if n > 2 { 1.0 / ((n - 1) * (n - 2)) as f64 } else { 0.0 };
+    betweenness
+        .into_iter()
+        .zip(closeness)
+        .map(|(betweenness, closeness)| SystemCentrality {
+            betweenness: betweenness * scale,
+            closeness,
+        })
+        .collect()
+}
+
+/// Reusable buffers for one single-source pass of Brandes' algorithm
+struct BrandesSearch {
+    distance: Vec<u32>,
+    paths: Vec<f64>,
+    dependency: Vec<f64>,
+    order: Vec<usize>,
+    queue: VecDeque<usize>,
+}
+
+impl BrandesSearch {
+    fn new(n: usize) -> Self {
+        Self {
+            distance: vec![u32::MAX; n],
+            paths: vec![0.0; n],
+            dependency: vec![0.0; n],
+            order: Vec::with_capacity(n),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Add `source`'s dependencies to `betweenness` and return its closeness
+    fn run(&mut self, adjacency: &[Vec<usize>], source: usize, betweenness: &mut [f64]) -> f64 {
+        let n = adjacency.len();
+        for &visited in &self.order {
+            self.distance[visited] = u32::MAX;
+            self.paths[visited] = 0.0;
+            self.dependency[visited] = 0.0;
+        }
+        self.order.clear();
+
+        self.distance[source] = 0;
+        self.paths[source] = 1.0;
+        self.queue.push_back(source);
+        let mut total_distance = 0u64;
+
+        while let Some(node) = self.queue.pop_front() {
+            self.order.push(node);
+            total_distance += u64::from(self.distance[node]);
+            for &next in &adjacency[node] {
+                if self.distance[next] == u32::MAX {
+                    self.distance[next] = self.distance[node] + 1;
+                    self.queue.push_back(next);
+                }
+                if self.distance[next] == self.distance[node] + 1 {
+                    self.paths[next] += self.paths[node];
+                }
+            }
+        }
+
+        // Accumulate dependencies farthest first; successors are the neighbours one step further out
+        for &node in self.order.iter().rev() {
+            let mut dependency = 0.0;
+            for &next in &adjacency[node] {
+                if self.distance[next] == self.distance[node] + 1 {
+                    dependency += self.paths[node] / self.paths[next] * (1.0 + self.dependency[next]);
+                }
+            }
+            self.dependency[node] = dependency;
+            if node != source {
+                betweenness[node] += dependency;
+            }
+        }
+
+        let reached = self.order.len() - 1;
+        if reached == 0 || n < 2 {
+            return 0.0;
+        }
+        let reached = reached as f64;
+        (reached / (n - 1) as f64) * (reached / total_distance as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::{connection, index_with_gates, system};
+    use super::super::SpatialIndex;
+    use super::{compute_centrality, find_chokepoints, Chokepoints, JumpEntry};
+    use crate::coordinates::Distance;
+    use crate::spatial::graph::GateGraph;
+
+    #[test]
+    fn test_chokepoints_of_two_triangles_joined_by_a_gate() {
+        // Triangles 1-2-3 and 4-5-6 linked by the 3-4 gate, plus a spur 6-7
+        let gates = [(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 6), (6, 4), (6, 7)];
+        let connections: Vec<_> = gates
+            .iter()
+            .enumerate()
+            .map(|(i, &(a, b))| connection(i as u32, a, b, "stargate"))
+            .collect();
+        let graph = GateGraph::from_connections(&connections);
+
+        let members: Vec<u32> = (1..=8).collect();
+        assert_eq!(
+            find_chokepoints(&graph, &members),
+            Chokepoints {
+                articulation_points: vec![3, 4, 6],
+                bridges: vec![(3, 4), (6, 7)],
+            }
+        );
+
+        // Restricting to one triangle leaves no chokepoints
+        assert_eq!(find_chokepoints(&graph, &[1, 2, 3]), Chokepoints::default());
+    }
+
+    #[test]
+    fn test_parallel_connections_are_not_bridges() {
+        let connections = vec![
+            connection(1, 1, 2, "stargate"),
+            connection(2, 1, 2, "jump_bridge"),
+            connection(3, 2, 3, "stargate"),
+        ];
+        let graph = GateGraph::from_connections(&connections);
+
+        assert_eq!(
+            find_chokepoints(&graph, &[1, 2, 3]),
+            Chokepoints {
+                articulation_points: vec![2],
+                bridges: vec![(2, 3)],
+            }
+        );
+    }
+
+    #[test]
+    fn test_region_chokepoints_ignore_outside_gates() {
+        // A square 1-2-3-4 where 1 and 2 are in region 10 and 3 and 4 in region 20
+        let mut systems = vec![
+            system(1, "A", [0.0, 0.0, 0.0]),
+            system(2, "B", [1.0, 0.0, 0.0]),
+            system(3, "C", [1.0, 1.0, 0.0]),
+            system(4, "D", [0.0, 1.0, 0.0]),
+        ];
+        for (i, region) in [10, 10, 20, 20].into_iter().enumerate() {
+            systems[i].1.region_id = Some(region);
+        }
+        let index: SpatialIndex = index_with_gates(systems, &[(1, 2), (2, 3), (3, 4), (4, 1)]);
+        let network = index.network_analysis();
+
+        assert_eq!(network.global, Chokepoints::default());
+        assert_eq!(network.regions[&10].bridges, vec![(1, 2)]);
+        assert_eq!(network.regions[&20].bridges, vec![(3, 4)]);
+    }
+
+    #[test]
+    fn test_centrality_of_a_star_and_a_path() {
+        // Hub 1 with spokes 2, 3, 4; spoke 4 continues to 5. System 6 has no gates.
+        let connections: Vec<_> = [(1, 2), (1, 3), (1, 4), (4, 5)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(a, b))| connection(i as u32, a, b, "stargate"))
+            .collect();
+        let graph = GateGraph::from_connections(&connections);
+        let centrality = compute_centrality(&graph, &[1, 2, 3, 4, 5]);
+
+        // Every pair except those involving only the hub routes through 1:
+        // (2,3) (2,4) (2,5) (3,4) (3,5) = 5 of 6 pairs excluding the hub
+        assert!((centrality[0].betweenness - 5.0 / 6.0).abs() < 1e-9);
+        // 4 carries (1,5) (2,5) (3,5)
+        assert!((centrality[3].betweenness - 3.0 / 6.0).abs() < 1e-9);
+        assert_eq!(centrality[1].betweenness, 0.0);
+
+        // The hub reaches everyone in 1 + 1 + 1 + 2 = 5 jumps
+        assert!((centrality[0].closeness - 4.0 / 5.0).abs() < 1e-9);
+        assert!(centrality[0].closeness > centrality[4].closeness);
+
+        // An isolated system is neither between nor close to anything
+        let centrality = compute_centrality(&graph, &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(centrality[5].betweenness, 0.0);
+        assert_eq!(centrality[5].closeness, 0.0);
+        // Closeness of the hub shrinks by the share of the network it cannot reach
+        assert!((centrality[0].closeness - (4.0 / 5.0) * (4.0 / 5.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_components_include_gate_less_systems_with_entry_points() {
+        // Main component 1-2-3, a pocket 4-5 off to the side and a lone system 6
+        let mut systems = vec![
+            system(1, "A", [0.0, 0.0, 0.0]),
+            system(2, "B", [1.0, 0.0, 0.0]),
+            system(3, "C", [2.0, 0.0, 0.0]),
+            system(4, "D", [2.0, 5.0, 0.0]),
+            system(5, "E", [2.0, 9.0, 0.0]),
+            system(6, "F", [-3.0, 0.0, 0.0]),
+        ];
+        for (i, region) in [10, 10, 10, 20, 30, 10].into_iter().enumerate() {
+            systems[i].1.region_id = Some(region);
+        }
+        let index = index_with_gates(systems, &[(1, 2), (2, 3), (4, 5)]);
+        let components = &index.network_analysis().components;
+
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0].systems, vec![1, 2, 3]);
+        assert_eq!(components[0].entry, None);
+
+        assert_eq!(components[1].systems, vec![4, 5]);
+        assert_eq!(components[1].regions, vec![20, 30]);
+        let entry = components[1].entry.unwrap();
+        assert_eq!((entry.from, entry.to), (3, 4));
+        assert!((Distance::from_meters(entry.distance).to_ly() - 5.0).abs() < 1e-6);
+
+        assert_eq!(components[2].systems, vec![6]);
+        assert!(matches!(components[2].entry, Some(JumpEntry { from: 1, to: 6, .. })));
+    }
+}
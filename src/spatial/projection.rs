@@ -0,0 +1,336 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::info;
+
+use super::graph::GateGraph;
+use super::{Point3D, SystemId};
+use crate::coordinates::METERS_PER_LIGHT_YEAR;
+use crate::models::{LayoutMethod, SolarSystem};
+
+/// Fruchterman-Reingold iterations per region
+const FORCE_ITERATIONS: usize = 200;
+
+pub type Point2D = [f64; 2];
+
+/// Precomputed 2D positions of one region's systems, in light-years
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionLayout {
+    /// Member systems sorted by ID; every position list follows this order
+    pub systems: Vec<SystemId>,
+    /// Gates between members as sorted `(lower, higher)` ID pairs
+    pub gates: Vec<(SystemId, SystemId)>,
+    /// Galactic (x, z) coordinates, looking down the Y axis
+    pub top_down: Vec<Point2D>,
+    /// Coordinates on the region's best-fit plane, relative to its centroid
+    pub pca: Vec<Point2D>,
+    /// Force-directed layout of the region's gate graph, seeded from `pca`
+    pub force_directed: Vec<Point2D>,
+}
+
+impl RegionLayout {
+    pub fn positions(&self, method: LayoutMethod) -> &[Point2D] {
+        match method {
+            LayoutMethod::TopDown => &self.top_down,
+            LayoutMethod::Pca => &self.pca,
+            LayoutMethod::ForceDirected => &self.force_directed,
+        }
+    }
+}
+
+/// Compute every region's layouts, spreading regions across threads
+pub fn compute_region_layouts(
+    graph: &GateGraph,
+    systems: &FxHashMap<SystemId, SolarSystem>,
+) -> FxHashMap<u32, RegionLayout> {
+    let started = Instant::now();
+
+    let mut by_region: FxHashMap<u32, Vec<SystemId>> = FxHashMap::default();
+    for (&id, system) in systems {
+        if let Some(region_id) = system.region_id {
+            by_region.entry(region_id).or_default().push(id);
+        }
+    }
+    let mut regions: Vec<(u32, Vec<SystemId>)> = by_region.into_iter().collect();
+    regions.sort_unstable_by_key(|(region_id, _)| *region_id);
+
+    let threads = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .clamp(1, regions.len().max(1));
+
+    let layouts: FxHashMap<u32, RegionLayout> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|worker| {
+                let regions = &regions;
+                scope.spawn(move || {
+                    regions
+                        .iter()
+                        .skip(worker)
+                        .step_by(threads)
+                        .map(|(region_id, members)| (*region_id, layout_region(graph, systems, members)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("layout worker panicked"))
+            .collect()
+    });
+
+    info!(
+        "Computed 2D layouts for {} regions in {:.2?}",
+        layouts.len(),
+        started.elapsed()
+    );
+    layouts
+}
+
+fn layout_region(
+    graph: &GateGraph,
+    systems: &FxHashMap<SystemId, SolarSystem>,
+    members: &[SystemId],
+) -> RegionLayout {
+    let mut members = members.to_vec();
+    members.sort_unstable();
+
+    let slot: FxHashMap<SystemId, usize> = members.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut edges: Vec<(usize, usize)> = members
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &id)| {
+            graph
+                .neighbours(id)
+                .iter()
+                .filter_map(|edge| slot.get(&edge.to).copied())
+                .filter(move |&j| i < j)
+                .map(move |j| (i, j))
+        })
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+
+    let points: Vec<Point3D> = members
+        .iter()
+        .map(|id| systems[id].center.map(|meters| meters / METERS_PER_LIGHT_YEAR))
+        .collect();
+
+    let top_down = points.iter().map(|point| [point[0], point[2]]).collect();
+    let pca = project_onto_best_fit_plane(&points);
+    let force_directed = force_directed_layout(&pca, &edges);
+
+    RegionLayout {
+        gates: edges.iter().map(|&(i, j)| (members[i], members[j])).collect(),
+        systems: members,
+        top_down,
+        pca,
+        force_directed,
+    }
+}
+
+/// Project points onto the plane spanned by their two principal axes.
+///
+/// Axis signs are fixed so each axis' largest component is positive, which
+/// keeps the layout from flipping between builds.
+pub fn project_onto_best_fit_plane(points: &[Point3D]) -> Vec<Point2D> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let count = points.len() as f64;
+    let centroid: Point3D = std::array::from_fn(|i| points.iter().map(|point| point[i]).sum::<f64>() / count);
+
+    let mut covariance = [[0.0; 3]; 3];
+    for point in points {
+        for row in 0..3 {
+            for col in 0..3 {
+                covariance[row][col] += (point[row] - centroid[row]) * (point[col] - centroid[col]) / count;
+            }
+        }
+    }
+
+    let (values, vectors) = symmetric_eigen(covariance);
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+
+    let axes: [Point3D; 2] = std::array::from_fn(|k| {
+        let column = order[k];
+        let mut axis: Point3D = std::array::from_fn(|row| vectors[row][column]);
+        let largest = (0..3)
+            .max_by(|&a, &b| axis[a].abs().total_cmp(&axis[b].abs()))
+            .unwrap_or(0);
+        if axis[largest] < 0.0 {
+            axis = axis.map(|component| -component);
+        }
+        axis
+    });
+
+    points
+        .iter()
+        .map(|point| {
+            let offset: Point3D = std::array::from_fn(|i| point[i] - centroid[i]);
+            axes.map(|axis| (0..3).map(|i| axis[i] * offset[i]).sum())
+        })
+        .collect()
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric 3x3 matrix by
+/// cyclic Jacobi rotation
+fn symmetric_eigen(mut matrix: [[f64; 3]; 3]) -> ([f64; 3], [[f64; 3]; 3]) {
+    let mut vectors = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..50 {
+        let off_diagonal = matrix[0][1].abs() + matrix[0][2].abs() + matrix[1][2].abs();
+        if off_diagonal < 1e-12 * (matrix[0][0].abs() + matrix[1][1].abs() + matrix[2][2].abs()).max(f64::MIN_POSITIVE) {
+            break;
+        }
+
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if matrix[p][q] == 0.0 {
+                continue;
+            }
+            let theta = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            // matrix = Jᵀ · matrix · J, with J the rotation in the (p, q) plane
+            for row in matrix.iter_mut() {
+                let (kp, kq) = (row[p], row[q]);
+                row[p] = c * kp - s * kq;
+                row[q] = s * kp + c * kq;
+            }
+            let (row_p, row_q) = (matrix[p], matrix[q]);
+            for k in 0..3 {
+                matrix[p][k] = c * row_p[k] - s * row_q[k];
+                matrix[q][k] = s * row_p[k] + c * row_q[k];
+            }
+            for row in vectors.iter_mut() {
+                let (vp, vq) = (row[p], row[q]);
+                row[p] = c * vp - s * vq;
+                row[q] = s * vp + c * vq;
+            }
+        }
+    }
+
+    ([matrix[0][0], matrix[1][1], matrix[2][2]], vectors)
+}
+
+/// Fruchterman-Reingold layout over `edges`, starting from `initial`.
+///
+/// The ideal edge length follows from the initial spread, and a pull towards
+/// the origin balances repulsion near that spread, so the result keeps roughly
+/// the same scale. Runs a fixed number of iterations with linear cooling and
+/// no randomness, so it is deterministic.
+pub fn force_directed_layout(initial: &[Point2D], edges: &[(usize, usize)]) -> Vec<Point2D> {
+    let n = initial.len();
+    let mut positions = initial.to_vec();
+    if n < 2 {
+        return positions;
+    }
+
+    let extent = positions
+        .iter()
+        .flat_map(|point| point.iter().map(|value| value.abs()))
+        .fold(0.0, f64::max)
+        .max(1.0);
+    let ideal = extent / (n as f64).sqrt();
+    let minimum = extent * 1e-6;
+
+    let mut displacement = vec![[0.0; 2]; n];
+    for iteration in 0..FORCE_ITERATIONS {
+        let temperature = extent / 10.0 * (1.0 - iteration as f64 / FORCE_ITERATIONS as f64);
+        displacement.iter_mut().for_each(|d| *d = [0.0; 2]);
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (direction, distance) = separation(positions[i], positions[j], i, j, minimum);
+                let force = ideal * ideal / distance;
+                for axis in 0..2 {
+                    displacement[i][axis] += direction[axis] * force;
+                    displacement[j][axis] -= direction[axis] * force;
+                }
+            }
+        }
+
+        for &(i, j) in edges {
+            let (direction, distance) = separation(positions[i], positions[j], i, j, minimum);
+            let force = distance * distance / ideal;
+            for axis in 0..2 {
+                displacement[i][axis] -= direction[axis] * force;
+                displacement[j][axis] += direction[axis] * force;
+            }
+        }
+
+        for (position, moved) in positions.iter_mut().zip(displacement.iter_mut()) {
+            // Keeps disconnected systems from drifting off
+            for axis in 0..2 {
+                moved[axis] -= position[axis];
+            }
+            let length = (moved[0] * moved[0] + moved[1] * moved[1]).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature) / length;
+                for axis in 0..2 {
+                    position[axis] += moved[axis] * step;
+                }
+            }
+        }
+    }
+    positions
+}
+
+/// Unit vector from `b` to `a` and their distance; coincident points are
+/// pushed apart along a fixed direction derived from their indices
+fn separation(a: Point2D, b: Point2D, i: usize, j: usize, minimum: f64) -> (Point2D, f64) {
+    let delta = [a[0] - b[0], a[1] - b[1]];
+    let distance = (delta[0] * delta[0] + delta[1] * delta[1]).sqrt();
+    if distance < minimum {
+        let angle = (i * 31 + j * 17) as f64;
+        return ([angle.cos(), angle.sin()], minimum);
+    }
+    ([delta[0] / distance, delta[1] / distance], distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{force_directed_layout, project_onto_best_fit_plane};
+
+    #[test]
+    fn test_pca_recovers_tilted_plane() {
+        // A 4 x 2 grid on the plane z = x, so distances within it are preserved
+        let points: Vec<[f64; 3]> = (0..4)
+            .flat_map(|i| (0..2).map(move |j| [i as f64, j as f64, i as f64]))
+            .collect();
+        let projected = project_onto_best_fit_plane(&points);
+
+        for a in 0..points.len() {
+            for b in 0..points.len() {
+                let original: f64 = (0..3).map(|k| (points[a][k] - points[b][k]).powi(2)).sum::<f64>().sqrt();
+                let flat: f64 = (0..2).map(|k| (projected[a][k] - projected[b][k]).powi(2)).sum::<f64>().sqrt();
+                assert!((original - flat).abs() < 1e-9);
+            }
+        }
+
+        // The long side of the grid becomes the first axis, centred on the centroid
+        assert!((projected[6][0] - projected[0][0] - 3.0 * 2f64.sqrt()).abs() < 1e-9);
+        assert!(projected.iter().map(|p| p[0]).sum::<f64>().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_force_layout_pulls_neighbours_together() {
+        // A path 0-1-2-3 seeded in a scrambled order along a line
+        let initial = vec![[0.0, 0.0], [3.0, 0.0], [1.0, 0.1], [2.0, -0.1]];
+        let edges = [(0, 1), (1, 2), (2, 3)];
+        let layout = force_directed_layout(&initial, &edges);
+        assert_eq!(layout, force_directed_layout(&initial, &edges));
+
+        let distance = |a: usize, b: usize| {
+            ((layout[a][0] - layout[b][0]).powi(2) + (layout[a][1] - layout[b][1]).powi(2)).sqrt()
+        };
+        // Linked systems end up closer than the path's two ends
+        for &(a, b) in &edges {
+            assert!(distance(a, b) < distance(0, 3));
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::routing::RouteOptions;
+use super::{Point3D, SpatialIndex, SystemId};
+use crate::models::RendezvousObjective;
+
+/// A candidate meeting system with every pilot's cost to reach it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rendezvous {
+    pub system_id: SystemId,
+    /// Route cost from each starting system, in the order given
+    pub costs: Vec<f64>,
+    pub max_cost: f64,
+    pub total_cost: f64,
+}
+
+impl Rendezvous {
+    fn score(&self, objective: RendezvousObjective) -> (f64, f64) {
+        match objective {
+            RendezvousObjective::Max => (self.max_cost, self.total_cost),
+            RendezvousObjective::Total => (self.total_cost, self.max_cost),
+        }
+    }
+}
+
+impl SpatialIndex {
+    /// Best systems for pilots starting at `starts` to meet in, best first.
+    ///
+    /// Candidates are the starting systems plus the `pool` systems nearest
+    /// their centroid; a candidate counts only when every pilot can reach it.
+    /// Costs come from the same search as `find_route`, so mode, jump range,
+    /// optimisation target, weights and constraints all apply. Ties on the
+    /// objective go to the lower value of the other aggregate, then lower ID.
+    pub fn find_rendezvous(
+        &self,
+        starts: &[SystemId],
+        pool: usize,
+        objective: RendezvousObjective,
+        limit: usize,
+        options: &RouteOptions,
+    ) -> Vec<Rendezvous> {
+        let positions: Vec<Point3D> = starts
+            .iter()
+            .filter_map(|id| self.systems.get(id).map(|s| s.center))
+            .collect();
+        if positions.is_empty() || positions.len() != starts.len() {
+            return Vec::new();
+        }
+
+        let count = positions.len() as f64;
+        let centroid: Point3D = std::array::from_fn(|i| positions.iter().map(|p| p[i]).sum::<f64>() / count);
+        let mut candidates: Vec<SystemId> = self
+            .find_nearest_systems(centroid, pool)
+            .into_iter()
+            .map(|(id, _)| id)
+            .chain(starts.iter().copied())
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let per_pilot: Vec<Vec<Option<f64>>> = starts
+            .iter()
+            .map(|&start| self.route_costs(start, &candidates, options))
+            .collect();
+
+        let mut found: Vec<Rendezvous> = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &system_id)| {
+                let costs = per_pilot.iter().map(|costs| costs[i]).collect::<Option<Vec<f64>>>()?;
+                Some(Rendezvous {
+                    system_id,
+                    max_cost: costs.iter().copied().fold(0.0, f64::max),
+                    total_cost: costs.iter().sum(),
+                    costs,
+                })
+            })
+            .collect();
+
+        found.sort_by(|a, b| {
+            let (a_first, a_second) = a.score(objective);
+            let (b_first, b_second) = b.score(objective);
+            a_first
+                .total_cmp(&b_first)
+                .then_with(|| a_second.total_cmp(&b_second))
+                .then_with(|| a.system_id.cmp(&b.system_id))
+        });
+        found.truncate(limit);
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::routing::RouteOptions;
+    use super::super::test_fixtures::{index_with_gates, system};
+    use crate::coordinates::Distance;
+    use crate::models::{RendezvousObjective, RouteMode, RouteOptimize};
+
+    #[test]
+    fn test_rendezvous_minimises_max_or_total() {
+        // A star: three arms of different length meeting at hub 1
+        //   2 - 1 - 3 - 4
+        //       |
+        //       5 - 6 - 7
+        let index = index_with_gates(
+            vec![
+                system(1, "Hub", [0.0, 0.0, 0.0]),
+                system(2, "West", [-1.0, 0.0, 0.0]),
+                system(3, "East", [1.0, 0.0, 0.0]),
+                system(4, "FarEast", [2.0, 0.0, 0.0]),
+                system(5, "South", [0.0, -1.0, 0.0]),
+                system(6, "FarSouth", [0.0, -2.0, 0.0]),
+                system(7, "Deep", [0.0, -3.0, 0.0]),
+                system(8, "Island", [0.5, 0.5, 0.0]),
+            ],
+            &[(2, 1), (1, 3), (3, 4), (1, 5), (5, 6), (6, 7)],
+        );
+        let options = RouteOptions::default();
+
+        let best = index.find_rendezvous(&[2, 4, 7], 10, RendezvousObjective::Max, 3, &options);
+        assert_eq!(best[0].system_id, 1);
+        assert_eq!(best[0].costs, vec![1.0, 2.0, 3.0]);
+        assert_eq!(best[0].max_cost, 3.0);
+        assert_eq!(best[0].total_cost, 6.0);
+        // The gate-less island is never a candidate
+        assert!(best.iter().all(|r| r.system_id != 8));
+
+        // Two pilots deep in the south outvote one in the west on total cost
+        let max = index.find_rendezvous(&[2, 6, 7], 10, RendezvousObjective::Max, 1, &options);
+        let total = index.find_rendezvous(&[2, 6, 7], 10, RendezvousObjective::Total, 1, &options);
+        assert_eq!(max[0].system_id, 5);
+        assert_eq!(total[0].system_id, 6);
+        assert_eq!(total[0].costs, vec![3.0, 0.0, 1.0]);
+
+        // A jump drive reaches the island
+        let jumps = RouteOptions {
+            mode: RouteMode::Mixed,
+            max_jump_range: Some(Distance::from_light_years(1.0).to_meters()),
+            optimize: RouteOptimize::Distance,
+            ..RouteOptions::default()
+        };
+        let mixed = index.find_rendezvous(&[8, 3], 10, RendezvousObjective::Max, 10, &jumps);
+        assert!(mixed.iter().any(|r| r.system_id == 8));
+        assert!((mixed[0].max_cost - 0.5f64.sqrt()).abs() < 1e-6);
+
+        assert!(index.find_rendezvous(&[2, 99], 10, RendezvousObjective::Max, 3, &options).is_empty());
+    }
+}
@@ -0,0 +1,923 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::BinaryHeap;
+
+use super::graph::ConnectionKind;
+use super::{Point3D, SpatialIndex, SystemId};
+use crate::coordinates::Distance;
+use crate::models::{RouteLink, RouteMode, RouteOptimize, SolarSystem};
+
+/// Resolved route planning options, with all distances in meters
+#[derive(Debug, Clone, Default)]
+pub struct RouteOptions {
+    pub mode: RouteMode,
+    /// Maximum jump drive range, required when jump drive hops are allowed
+    pub max_jump_range: Option<f64>,
+    pub optimize: RouteOptimize,
+    pub weights: EdgeWeights,
+    pub constraints: RouteConstraints,
+}
+
+/// Systems a route must not pass through. The origin is always allowed.
+#[derive(Debug, Clone, Default)]
+pub struct RouteConstraints {
+    pub systems: FxHashSet<SystemId>,
+    pub constellations: FxHashSet<u32>,
+    pub regions: FxHashSet<u32>,
+    /// Compared case-insensitively; store lowercase
+    pub security_classes: FxHashSet<String>,
+    pub factions: FxHashSet<u32>,
+}
+
+/// Category of a route constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    System,
+    Constellation,
+    Region,
+    SecurityClass,
+    Faction,
+}
+
+impl ConstraintKind {
+    pub const ALL: [ConstraintKind; 5] = [
+        ConstraintKind::System,
+        ConstraintKind::Constellation,
+        ConstraintKind::Region,
+        ConstraintKind::SecurityClass,
+        ConstraintKind::Faction,
+    ];
+}
+
+impl RouteConstraints {
+    pub fn is_empty(&self) -> bool {
+        ConstraintKind::ALL.iter().all(|kind| !self.is_active(*kind))
+    }
+
+    pub fn is_active(&self, kind: ConstraintKind) -> bool {
+        match kind {
+            ConstraintKind::System => !self.systems.is_empty(),
+            ConstraintKind::Constellation => !self.constellations.is_empty(),
+            ConstraintKind::Region => !self.regions.is_empty(),
+            ConstraintKind::SecurityClass => !self.security_classes.is_empty(),
+            ConstraintKind::Faction => !self.factions.is_empty(),
+        }
+    }
+
+    /// The first constraint that excludes `system`, if any
+    pub fn violation(&self, system: &SolarSystem) -> Option<ConstraintKind> {
+        if self.systems.contains(&system.id) {
+            return Some(ConstraintKind::System);
+        }
+        if system.constellation_id.is_some_and(|id| self.constellations.contains(&id)) {
+            return Some(ConstraintKind::Constellation);
+        }
+        if system.region_id.is_some_and(|id| self.regions.contains(&id)) {
+            return Some(ConstraintKind::Region);
+        }
+        if system
+            .security
+            .class
+            .as_ref()
+            .is_some_and(|class| self.security_classes.contains(&class.to_lowercase()))
+        {
+            return Some(ConstraintKind::SecurityClass);
+        }
+        if system.metadata.faction_id.is_some_and(|id| self.factions.contains(&id)) {
+            return Some(ConstraintKind::Faction);
+        }
+        None
+    }
+
+    /// A copy with one category of constraint lifted
+    pub fn without(&self, kind: ConstraintKind) -> Self {
+        let mut relaxed = self.clone();
+        match kind {
+            ConstraintKind::System => relaxed.systems.clear(),
+            ConstraintKind::Constellation => relaxed.constellations.clear(),
+            ConstraintKind::Region => relaxed.regions.clear(),
+            ConstraintKind::SecurityClass => relaxed.security_classes.clear(),
+            ConstraintKind::Faction => relaxed.factions.clear(),
+        }
+        relaxed
+    }
+}
+
+/// Per-edge-kind multipliers applied to the base cost of each hop.
+///
+/// The base cost is 1 per hop when optimising for jumps, or the hop length in
+/// light-years when optimising for distance.
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeWeights {
+    pub stargate: f64,
+    pub jump_bridge: f64,
+    pub wormhole: f64,
+    pub jump: f64,
+}
+
+impl Default for EdgeWeights {
+    fn default() -> Self {
+        Self {
+            stargate: 1.0,
+            jump_bridge: 1.0,
+            wormhole: 1.0,
+            jump: 1.0,
+        }
+    }
+}
+
+impl EdgeWeights {
+    pub fn for_link(&self, link: RouteLink) -> f64 {
+        match link {
+            RouteLink::Stargate => self.stargate,
+            RouteLink::JumpBridge => self.jump_bridge,
+            RouteLink::Wormhole => self.wormhole,
+            RouteLink::Jump => self.jump,
+        }
+    }
+}
+
+impl From<ConnectionKind> for RouteLink {
+    fn from(kind: ConnectionKind) -> Self {
+        match kind {
+            ConnectionKind::Stargate => RouteLink::Stargate,
+            ConnectionKind::JumpBridge => RouteLink::JumpBridge,
+            ConnectionKind::Wormhole => RouteLink::Wormhole,
+        }
+    }
+}
+
+/// A planned route
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePlan {
+    /// Systems in travel order including both endpoints
+    pub systems: Vec<SystemId>,
+    /// `links[i]` is how the route moves from `systems[i]` to `systems[i + 1]`
+    pub links: Vec<RouteLink>,
+    /// Total cost under the options' cost model (jumps or light-years, weighted)
+    pub cost: f64,
+}
+
+/// A system found by a reachability search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reachable {
+    pub system_id: SystemId,
+    /// Fewest hops needed to get here from the origin
+    pub hops: u32,
+    /// System this one is reached from on a fewest-hop path (`None` for the origin)
+    pub previous: Option<SystemId>,
+    /// How the last hop is made (`None` for the origin)
+    pub via: Option<RouteLink>,
+}
+
+/// Priority queue entry for A*, ordered so the most promising entry pops first
+#[derive(Debug, Clone, Copy)]
+struct Frontier {
+    /// Cost so far plus the heuristic estimate to the goal
+    priority: f64,
+    cost: f64,
+    system_id: SystemId,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so BinaryHeap behaves as a min-heap
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.system_id.cmp(&self.system_id))
+    }
+}
+
+fn uses_gates(mode: RouteMode) -> bool {
+    matches!(mode, RouteMode::Gate | RouteMode::Mixed)
+}
+
+fn uses_jumps(mode: RouteMode) -> bool {
+    matches!(mode, RouteMode::Jump | RouteMode::Mixed)
+}
+
+impl SpatialIndex {
+    /// Find the cheapest route between two systems under the given options,
+    /// or `None` when no route exists.
+    pub fn find_route(&self, from: SystemId, to: SystemId, options: &RouteOptions) -> Option<RoutePlan> {
+        let target = self.systems.get(&to)?.center;
+
+        shortest_path(
+            from,
+            to,
+            |system_id, edges| self.expand_route_edges(system_id, options, edges),
+            |system_id| self.route_heuristic(system_id, target, options),
+        )
+    }
+
+    /// Explain why `find_route` returned `None` for a constrained request.
+    ///
+    /// Returns the constraint categories that block the route: those whose
+    /// removal alone makes the destination reachable, or every active category
+    /// when only a combination of them is to blame. Returns an empty list when
+    /// the destination is unreachable even without constraints.
+    pub fn find_blocking_constraints(&self, from: SystemId, to: SystemId, options: &RouteOptions) -> Vec<ConstraintKind> {
+        let active: Vec<ConstraintKind> = ConstraintKind::ALL
+            .into_iter()
+            .filter(|kind| options.constraints.is_active(*kind))
+            .collect();
+        if active.is_empty() {
+            return Vec::new();
+        }
+
+        // An excluded destination is blocked by definition
+        if let Some(kind) = self.systems.get(&to).and_then(|s| options.constraints.violation(s)) {
+            if from != to {
+                return vec![kind];
+            }
+        }
+
+        let unconstrained = RouteOptions {
+            constraints: RouteConstraints::default(),
+            ..options.clone()
+        };
+        if self.find_route(from, to, &unconstrained).is_none() {
+            return Vec::new();
+        }
+
+        let individually_blocking: Vec<ConstraintKind> = active
+            .iter()
+            .copied()
+            .filter(|kind| {
+                let relaxed = RouteOptions {
+                    constraints: options.constraints.without(*kind),
+                    ..options.clone()
+                };
+                self.find_route(from, to, &relaxed).is_some()
+            })
+            .collect();
+
+        if individually_blocking.is_empty() {
+            active
+        } else {
+            individually_blocking
+        }
+    }
+
+    /// Every system reachable from `from` within `max_hops` hops, in breadth-first
+    /// order starting with the origin itself.
+    ///
+    /// Hops are counted regardless of `options.optimize` and edge weights; the
+    /// mode, jump range and constraints decide which hops are possible.
+    pub fn reachable_within(&self, from: SystemId, max_hops: u32, options: &RouteOptions) -> Vec<Reachable> {
+        if !self.systems.contains_key(&from) {
+            return Vec::new();
+        }
+
+        let mut found = vec![Reachable { system_id: from, hops: 0, previous: None, via: None }];
+        let mut seen: FxHashSet<SystemId> = [from].into_iter().collect();
+        let mut frontier_start = 0;
+        let mut edges = Vec::new();
+
+        for hops in 1..=max_hops {
+            let frontier_end = found.len();
+            for i in frontier_start..frontier_end {
+                let current = found[i].system_id;
+                edges.clear();
+                self.expand_route_edges(current, options, &mut edges);
+                for &(next, _, link) in &edges {
+                    if seen.insert(next) {
+                        found.push(Reachable { system_id: next, hops, previous: Some(current), via: Some(link) });
+                    }
+                }
+            }
+            if found.len() == frontier_end {
+                break;
+            }
+            frontier_start = frontier_end;
+        }
+
+        found
+    }
+
+    /// Fewest hops from `from` to each of `targets`, in the same order, or
+    /// `None` where a target cannot be reached. The search stops as soon as
+    /// every target has been found.
+    pub fn hop_counts(&self, from: SystemId, targets: &[SystemId], options: &RouteOptions) -> Vec<Option<u32>> {
+        let mut hops: FxHashMap<SystemId, u32> = FxHashMap::default();
+        if !self.systems.contains_key(&from) {
+            return vec![None; targets.len()];
+        }
+        hops.insert(from, 0);
+
+        let mut remaining: FxHashSet<SystemId> = targets.iter().copied().filter(|&id| id != from).collect();
+        let mut frontier = vec![from];
+        let mut edges = Vec::new();
+        let mut depth = 0;
+
+        while !remaining.is_empty() && !frontier.is_empty() {
+            depth += 1;
+            let mut next_frontier = Vec::new();
+            for &current in &frontier {
+                edges.clear();
+                self.expand_route_edges(current, options, &mut edges);
+                for &(next, _, _) in &edges {
+                    if let Entry::Vacant(entry) = hops.entry(next) {
+                        entry.insert(depth);
+                        remaining.remove(&next);
+                        next_frontier.push(next);
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        targets.iter().map(|id| hops.get(id).copied()).collect()
+    }
+
+    /// Cheapest route cost from `from` to each of `targets`, in the same
+    /// order, or `None` where a target cannot be reached. Costs match
+    /// `find_route`; the search stops once every target is settled.
+    pub fn route_costs(&self, from: SystemId, targets: &[SystemId], options: &RouteOptions) -> Vec<Option<f64>> {
+        if !self.systems.contains_key(&from) {
+            return vec![None; targets.len()];
+        }
+
+        let mut remaining: FxHashSet<SystemId> = targets.iter().copied().collect();
+        let mut best: FxHashMap<SystemId, f64> = FxHashMap::default();
+        let mut settled: FxHashMap<SystemId, f64> = FxHashMap::default();
+        let mut heap = BinaryHeap::new();
+        let mut edges = Vec::new();
+        best.insert(from, 0.0);
+        heap.push(Frontier { priority: 0.0, cost: 0.0, system_id: from });
+
+        while let Some(Frontier { cost, system_id, .. }) = heap.pop() {
+            if settled.contains_key(&system_id) {
+                continue;
+            }
+            settled.insert(system_id, cost);
+            remaining.remove(&system_id);
+            if remaining.is_empty() {
+                break;
+            }
+
+            edges.clear();
+            self.expand_route_edges(system_id, options, &mut edges);
+            for &(next, edge, _) in &edges {
+                let next_cost = cost + edge;
+                if best.get(&next).is_none_or(|&known| next_cost < known) {
+                    best.insert(next, next_cost);
+                    heap.push(Frontier { priority: next_cost, cost: next_cost, system_id: next });
+                }
+            }
+        }
+
+        targets.iter().map(|id| settled.get(id).copied()).collect()
+    }
+
+    /// Up to `k` loopless routes in increasing cost order, best first (Yen's algorithm).
+    ///
+    /// Routes differ in the sequence of systems visited; two routes through the
+    /// same systems by different links count as one.
+    pub fn find_alternative_routes(&self, from: SystemId, to: SystemId, k: usize, options: &RouteOptions) -> Vec<RoutePlan> {
+        let Some(target) = self.systems.get(&to).map(|s| s.center) else {
+            return Vec::new();
+        };
+        let Some(best) = self.find_route(from, to, options) else {
+            return Vec::new();
+        };
+
+        let mut accepted = vec![best];
+        let mut candidates: Vec<RoutePlan> = Vec::new();
+
+        while accepted.len() < k {
+            let previous = accepted[accepted.len() - 1].clone();
+
+            for spur_index in 0..previous.systems.len().saturating_sub(1) {
+                let spur = previous.systems[spur_index];
+                let root = &previous.systems[..=spur_index];
+
+                // Edges already taken from this root by accepted routes, and the
+                // root itself, are off limits so the spur must branch off fresh
+                let removed_edges: FxHashSet<(SystemId, SystemId)> = accepted
+                    .iter()
+                    .filter(|plan| plan.systems.len() > spur_index + 1 && plan.systems[..=spur_index] == *root)
+                    .map(|plan| (spur, plan.systems[spur_index + 1]))
+                    .collect();
+                let removed_systems: FxHashSet<SystemId> = root[..spur_index].iter().copied().collect();
+
+                let Some(spur_plan) = shortest_path(
+                    spur,
+                    to,
+                    |system_id, edges| {
+                        self.expand_route_edges(system_id, options, edges);
+                        edges.retain(|(next, _, _)| {
+                            !removed_systems.contains(next) && !removed_edges.contains(&(system_id, *next))
+                        });
+                    },
+                    |system_id| self.route_heuristic(system_id, target, options),
+                ) else {
+                    continue;
+                };
+
+                let mut systems = root.to_vec();
+                systems.extend_from_slice(&spur_plan.systems[1..]);
+                let mut links = previous.links[..spur_index].to_vec();
+                links.extend_from_slice(&spur_plan.links);
+                let root_cost: f64 = (0..spur_index)
+                    .map(|i| self.hop_cost(previous.systems[i], previous.systems[i + 1], previous.links[i], options))
+                    .sum();
+                let candidate = RoutePlan { systems, links, cost: root_cost + spur_plan.cost };
+
+                let known = accepted
+                    .iter()
+                    .chain(candidates.iter())
+                    .any(|plan| plan.systems == candidate.systems);
+                if !known {
+                    candidates.push(candidate);
+                }
+            }
+
+            // Cheapest candidate next, preferring fewer hops on ties
+            let Some(next) = candidates
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.cost
+                        .total_cmp(&b.cost)
+                        .then_with(|| a.systems.len().cmp(&b.systems.len()))
+                })
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+            accepted.push(candidates.swap_remove(next));
+        }
+
+        accepted
+    }
+
+    /// Cost of one hop of a known route
+    fn hop_cost(&self, from: SystemId, to: SystemId, link: RouteLink, options: &RouteOptions) -> f64 {
+        match (self.systems.get(&from), self.systems.get(&to)) {
+            (Some(a), Some(b)) => edge_cost(link, euclidean(a.center, b.center), options),
+            _ => 0.0,
+        }
+    }
+
+    fn allows(&self, system_id: SystemId, options: &RouteOptions) -> bool {
+        options.constraints.is_empty()
+            || self
+                .systems
+                .get(&system_id)
+                .is_some_and(|system| options.constraints.violation(system).is_none())
+    }
+
+    /// Push every edge leaving `system_id` together with its cost
+    fn expand_route_edges(
+        &self,
+        system_id: SystemId,
+        options: &RouteOptions,
+        edges: &mut Vec<(SystemId, f64, RouteLink)>,
+    ) {
+        let Some(origin) = self.systems.get(&system_id).map(|s| s.center) else {
+            return;
+        };
+
+        if uses_gates(options.mode) {
+            for edge in self.gate_graph.neighbours(system_id) {
+                if !self.allows(edge.to, options) {
+                    continue;
+                }
+                if let Some(destination) = self.systems.get(&edge.to) {
+                    let link = RouteLink::from(edge.kind);
+                    let length = euclidean(origin, destination.center);
+                    edges.push((edge.to, edge_cost(link, length, options), link));
+                }
+            }
+        }
+
+        if uses_jumps(options.mode) {
+            if let Some(range) = options.max_jump_range {
+                for (neighbour, distance) in self.find_systems_within_radius(origin, range) {
+                    if neighbour != system_id && self.allows(neighbour, options) {
+                        edges.push((neighbour, edge_cost(RouteLink::Jump, distance, options), RouteLink::Jump));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Admissible lower bound on the remaining cost from `system_id` to `target`
+    fn route_heuristic(&self, system_id: SystemId, target: Point3D, options: &RouteOptions) -> f64 {
+        let Some(position) = self.systems.get(&system_id).map(|s| s.center) else {
+            return 0.0;
+        };
+        let remaining = euclidean(position, target);
+
+        match options.optimize {
+            // No hop can be cheaper per light-year than the cheapest enabled edge kind
+            RouteOptimize::Distance => Distance::from_meters(remaining).to_ly() * cheapest_weight(options),
+            // Each jump drive hop covers at most the drive range
+            RouteOptimize::Jumps if options.mode == RouteMode::Jump => options
+                .max_jump_range
+                .filter(|range| *range > 0.0)
+                .map(|range| (remaining / range) * options.weights.jump)
+                .unwrap_or(0.0),
+            // A single gate can span any distance
+            RouteOptimize::Jumps => 0.0,
+        }
+    }
+}
+
+/// Cost of a single hop of `length` meters
+fn edge_cost(link: RouteLink, length: f64, options: &RouteOptions) -> f64 {
+    let base = match options.optimize {
+        RouteOptimize::Jumps => 1.0,
+        RouteOptimize::Distance => Distance::from_meters(length).to_ly(),
+    };
+    base * options.weights.for_link(link)
+}
+
+fn cheapest_weight(options: &RouteOptions) -> f64 {
+    let mut links = Vec::with_capacity(4);
+    if uses_gates(options.mode) {
+        links.extend([RouteLink::Stargate, RouteLink::JumpBridge, RouteLink::Wormhole]);
+    }
+    if uses_jumps(options.mode) {
+        links.push(RouteLink::Jump);
+    }
+
+    links
+        .into_iter()
+        .map(|link| options.weights.for_link(link))
+        .fold(f64::INFINITY, f64::min)
+}
+
+pub fn euclidean(a: Point3D, b: Point3D) -> f64 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = a[2] - b[2];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// A* search over an implicit graph.
+///
+/// `expand` pushes `(neighbour, edge_cost, link)` triples for a system into the
+/// supplied buffer; edge costs must be non-negative. `heuristic` must never
+/// overestimate the remaining cost (return 0 for plain Dijkstra).
+fn shortest_path<F, H>(from: SystemId, to: SystemId, mut expand: F, heuristic: H) -> Option<RoutePlan>
+where
+    F: FnMut(SystemId, &mut Vec<(SystemId, f64, RouteLink)>),
+    H: Fn(SystemId) -> f64,
+{
+    let mut best_cost: FxHashMap<SystemId, f64> = FxHashMap::default();
+    let mut previous: FxHashMap<SystemId, (SystemId, RouteLink)> = FxHashMap::default();
+    let mut heap = BinaryHeap::new();
+    let mut edges = Vec::new();
+
+    best_cost.insert(from, 0.0);
+    heap.push(Frontier { priority: heuristic(from), cost: 0.0, system_id: from });
+
+    while let Some(Frontier { cost, system_id, .. }) = heap.pop() {
+        if system_id == to {
+            let mut systems = vec![to];
+            let mut links = Vec::new();
+            let mut current = to;
+            while let Some(&(prev, link)) = previous.get(&current) {
+                systems.push(prev);
+                links.push(link);
+                current = prev;
+            }
+            systems.reverse();
+            links.reverse();
+            return Some(RoutePlan { systems, links, cost });
+        }
+
+        // Skip stale heap entries
+        if cost > best_cost.get(&system_id).copied().unwrap_or(f64::INFINITY) {
+            continue;
+        }
+
+        edges.clear();
+        expand(system_id, &mut edges);
+        for &(neighbour, edge_cost, link) in &edges {
+            let next_cost = cost + edge_cost;
+            if next_cost < best_cost.get(&neighbour).copied().unwrap_or(f64::INFINITY) {
+                best_cost.insert(neighbour, next_cost);
+                previous.insert(neighbour, (system_id, link));
+                heap.push(Frontier {
+                    priority: next_cost + heuristic(neighbour),
+                    cost: next_cost,
+                    system_id: neighbour,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::{connection, index_with_gates, system};
+    use super::super::{SpatialIndex, SystemId};
+    use super::{ConstraintKind, EdgeWeights, RouteConstraints, RouteOptions};
+    use crate::coordinates::Distance;
+    use crate::models::{RouteLink, RouteMode, RouteOptimize};
+
+    fn route(index: &SpatialIndex, from: SystemId, to: SystemId, options: &RouteOptions) -> Option<Vec<SystemId>> {
+        index.find_route(from, to, options).map(|plan| plan.systems)
+    }
+
+    fn jump_options(range_ly: f64, optimize: RouteOptimize) -> RouteOptions {
+        RouteOptions {
+            mode: RouteMode::Jump,
+            max_jump_range: Some(Distance::from_light_years(range_ly).to_meters()),
+            optimize,
+            ..RouteOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_gate_route_prefers_fewest_jumps() {
+        // 1 - 2 - 3 - 4 and a shortcut 1 - 5 - 4
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+                system(4, "D", [3.0, 0.0, 0.0]),
+                system(5, "E", [1.5, 5.0, 0.0]),
+            ],
+            &[(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)],
+        );
+
+        assert_eq!(route(&index, 1, 4, &RouteOptions::default()), Some(vec![1, 5, 4]));
+        assert_eq!(route(&index, 4, 1, &RouteOptions::default()), Some(vec![4, 5, 1]));
+    }
+
+    #[test]
+    fn test_gate_route_same_system() {
+        let index = index_with_gates(vec![system(1, "A", [0.0, 0.0, 0.0])], &[]);
+        assert_eq!(route(&index, 1, 1, &RouteOptions::default()), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_gate_route_unreachable() {
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+            ],
+            &[(1, 2)],
+        );
+        assert_eq!(route(&index, 1, 3, &RouteOptions::default()), None);
+    }
+
+    #[test]
+    fn test_gate_route_shortest_distance() {
+        // Same graph as above: 1-2-3-4 is 3 ly, 1-5-4 is roughly 10.4 ly
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+                system(4, "D", [3.0, 0.0, 0.0]),
+                system(5, "E", [1.5, 5.0, 0.0]),
+            ],
+            &[(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)],
+        );
+        let options = RouteOptions {
+            optimize: RouteOptimize::Distance,
+            ..RouteOptions::default()
+        };
+
+        assert_eq!(route(&index, 1, 4, &options), Some(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_jump_route_respects_range() {
+        // A straight line of short hops with an off-axis stepping stone at 5
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [4.0, 0.0, 0.0]),
+                system(3, "C", [12.0, 0.0, 0.0]),
+                system(4, "D", [16.0, 0.0, 0.0]),
+                system(5, "E", [8.0, 2.0, 0.0]),
+            ],
+            &[],
+        );
+
+        // 5 ly range has to visit every system
+        assert_eq!(
+            route(&index, 1, 4, &jump_options(5.0, RouteOptimize::Jumps)),
+            Some(vec![1, 2, 5, 3, 4])
+        );
+        // 8.5 ly range crosses in two jumps through the stepping stone
+        assert_eq!(
+            route(&index, 1, 4, &jump_options(8.5, RouteOptimize::Jumps)),
+            Some(vec![1, 5, 4])
+        );
+        // Distance optimisation stays on the straight line instead
+        assert_eq!(
+            route(&index, 1, 4, &jump_options(8.5, RouteOptimize::Distance)),
+            Some(vec![1, 2, 3, 4])
+        );
+        // Too short to reach anything
+        assert_eq!(route(&index, 1, 4, &jump_options(3.0, RouteOptimize::Jumps)), None);
+    }
+
+    #[test]
+    fn test_mixed_route_uses_edge_weights() {
+        // A long gate detour 1-2-3-4 and a jump stepping stone at 5
+        let systems = vec![
+            system(1, "A", [0.0, 0.0, 0.0]),
+            system(2, "B", [0.0, 30.0, 0.0]),
+            system(3, "C", [20.0, 30.0, 0.0]),
+            system(4, "D", [20.0, 0.0, 0.0]),
+            system(5, "E", [10.0, 0.0, 0.0]),
+        ];
+        let gates = [
+            connection(1, 1, 2, "stargate"),
+            connection(2, 2, 3, "stargate"),
+            connection(3, 3, 4, "stargate"),
+        ];
+        let index = SpatialIndex::from_parts(systems.clone(), Vec::new(), Vec::new(), gates.to_vec());
+
+        let mut options = RouteOptions {
+            mode: RouteMode::Mixed,
+            max_jump_range: Some(Distance::from_light_years(12.0).to_meters()),
+            ..RouteOptions::default()
+        };
+
+        // Two jump drive hops beat three gates
+        let plan = index.find_route(1, 4, &options).unwrap();
+        assert_eq!(plan.systems, vec![1, 5, 4]);
+        assert_eq!(plan.links, vec![RouteLink::Jump, RouteLink::Jump]);
+
+        // Free gates with distance-priced jumps prefer the detour
+        options.optimize = RouteOptimize::Distance;
+        options.weights.stargate = 0.0;
+        let plan = index.find_route(1, 4, &options).unwrap();
+        assert_eq!(plan.systems, vec![1, 2, 3, 4]);
+        assert_eq!(plan.cost, 0.0);
+
+        // A cheap wormhole straight to the destination wins outright
+        let mut connections = gates.to_vec();
+        connections.push(connection(4, 1, 4, "wormhole"));
+        let index = SpatialIndex::from_parts(systems, Vec::new(), Vec::new(), connections);
+        let options = RouteOptions {
+            mode: RouteMode::Mixed,
+            max_jump_range: Some(Distance::from_light_years(12.0).to_meters()),
+            weights: EdgeWeights { wormhole: 0.5, ..EdgeWeights::default() },
+            ..RouteOptions::default()
+        };
+        let plan = index.find_route(1, 4, &options).unwrap();
+        assert_eq!(plan.links, vec![RouteLink::Wormhole]);
+        assert_eq!(plan.cost, 0.5);
+    }
+
+    #[test]
+    fn test_hop_counts_to_targets() {
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+                system(4, "D", [3.0, 0.0, 0.0]),
+                system(5, "E", [9.0, 0.0, 0.0]),
+            ],
+            &[(1, 2), (2, 3), (3, 4), (1, 3)],
+        );
+
+        let hops = index.hop_counts(1, &[4, 1, 2, 5, 99], &RouteOptions::default());
+        assert_eq!(hops, vec![Some(2), Some(0), Some(1), None, None]);
+        assert_eq!(index.hop_counts(99, &[1], &RouteOptions::default()), vec![None]);
+    }
+
+    #[test]
+    fn test_reachable_within_hop_budget() {
+        // Gates 1-2-3-4 plus a gate-less system 5 within 2.5 ly of 2
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+                system(4, "D", [3.0, 0.0, 0.0]),
+                system(5, "E", [1.0, 2.0, 0.0]),
+            ],
+            &[(1, 2), (2, 3), (3, 4)],
+        );
+
+        let reached = index.reachable_within(1, 2, &RouteOptions::default());
+        let ids: Vec<SystemId> = reached.iter().map(|r| r.system_id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(reached[2].hops, 2);
+        assert_eq!(reached[2].previous, Some(2));
+        assert_eq!(reached[2].via, Some(RouteLink::Stargate));
+
+        // A jump drive adds the gate-less system one hop out from 1 or 2
+        let options = RouteOptions {
+            mode: RouteMode::Mixed,
+            max_jump_range: Some(Distance::from_light_years(2.5).to_meters()),
+            ..RouteOptions::default()
+        };
+        let reached = index.reachable_within(1, 1, &options);
+        let mut ids: Vec<SystemId> = reached.iter().map(|r| r.system_id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2, 3, 5]);
+
+        assert_eq!(index.reachable_within(1, 0, &options).len(), 1);
+    }
+
+    #[test]
+    fn test_alternative_routes_are_loopless_and_ordered() {
+        // Three corridors from 1 to 6 of two, three and four jumps
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [1.0, 1.0, 0.0]),
+                system(4, "D", [2.0, 1.0, 0.0]),
+                system(5, "E", [1.0, -1.0, 0.0]),
+                system(6, "F", [3.0, 0.0, 0.0]),
+                system(7, "G", [2.0, -1.0, 0.0]),
+                system(8, "H", [2.5, -1.0, 0.0]),
+            ],
+            &[(1, 2), (2, 6), (1, 3), (3, 4), (4, 6), (1, 5), (5, 7), (7, 8), (8, 6), (2, 4)],
+        );
+
+        let plans = index.find_alternative_routes(1, 6, 10, &RouteOptions::default());
+        assert!(plans.windows(2).all(|pair| pair[0].cost <= pair[1].cost));
+        let routes: Vec<Vec<SystemId>> = plans.into_iter().map(|plan| plan.systems).collect();
+
+        assert_eq!(routes[0], vec![1, 2, 6]);
+        assert_eq!(routes.len(), 5);
+        assert!(routes.contains(&vec![1, 5, 7, 8, 6]));
+        assert!(routes.contains(&vec![1, 3, 4, 6]));
+        assert!(routes.contains(&vec![1, 2, 4, 6]));
+        assert!(routes.contains(&vec![1, 3, 4, 2, 6]));
+        for route in &routes {
+            let mut unique = route.clone();
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), route.len(), "route {:?} revisits a system", route);
+        }
+
+        // Asking for fewer returns the cheapest ones only
+        let plans = index.find_alternative_routes(1, 6, 2, &RouteOptions::default());
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[1].cost, 3.0);
+    }
+
+    #[test]
+    fn test_constraints_detour_and_report_blockers() {
+        // Two parallel corridors from 1 to 4: through 2 (region 10) or 3 (region 20)
+        let mut systems = vec![
+            system(1, "A", [0.0, 0.0, 0.0]),
+            system(2, "B", [1.0, 1.0, 0.0]),
+            system(3, "C", [1.0, -1.0, 0.0]),
+            system(4, "D", [2.0, 0.0, 0.0]),
+        ];
+        systems[1].1.region_id = Some(10);
+        systems[2].1.region_id = Some(20);
+        systems[2].1.metadata.faction_id = Some(500001);
+        let index = index_with_gates(systems, &[(1, 2), (2, 4), (1, 3), (3, 4)]);
+
+        let mut options = RouteOptions::default();
+        options.constraints.regions.insert(10);
+        assert_eq!(route(&index, 1, 4, &options), Some(vec![1, 3, 4]));
+
+        // Avoiding both corridors blocks the route; either constraint alone is enough to lift
+        options.constraints.factions.insert(500001);
+        assert_eq!(route(&index, 1, 4, &options), None);
+        assert_eq!(
+            index.find_blocking_constraints(1, 4, &options),
+            vec![ConstraintKind::Region, ConstraintKind::Faction]
+        );
+
+        // An avoided destination names the constraint that excludes it
+        let options = RouteOptions {
+            constraints: RouteConstraints {
+                systems: [4].into_iter().collect(),
+                ..RouteConstraints::default()
+            },
+            ..RouteOptions::default()
+        };
+        assert_eq!(index.find_blocking_constraints(1, 4, &options), vec![ConstraintKind::System]);
+    }
+}
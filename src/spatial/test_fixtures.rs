@@ -0,0 +1,67 @@
+//! Small hand-built indexes for unit tests
+
+use std::collections::HashMap;
+
+use super::{SpatialIndex, SystemId};
+use crate::coordinates::Coordinate3D;
+use crate::models::{
+    CelestialInfo, GateConnection, NavigationInfo, SecurityInfo, SolarSystem, SystemMetadata,
+};
+
+/// A bare system positioned in light-years
+pub fn system(id: SystemId, name: &str, position_ly: [f64; 3]) -> (SystemId, SolarSystem, String) {
+    let center = Coordinate3D::from_light_years(position_ly[0], position_ly[1], position_ly[2])
+        .to_meters_array();
+
+    let solar_system = SolarSystem {
+        id,
+        name: name.to_string(),
+        center,
+        region_id: None,
+        constellation_id: None,
+        security: SecurityInfo {
+            class: None,
+            status: None,
+        },
+        celestials: CelestialInfo {
+            star_id: None,
+            planet_ids: Vec::new(),
+            planet_count_by_type: HashMap::new(),
+        },
+        navigation: NavigationInfo {
+            neighbours: Vec::new(),
+            stargates: Vec::new(),
+        },
+        metadata: SystemMetadata {
+            faction_id: None,
+            sovereignty: None,
+            disallowed_anchor_categories: Vec::new(),
+            disallowed_anchor_groups: Vec::new(),
+        },
+    };
+
+    (id, solar_system, name.to_string())
+}
+
+/// Build an index from systems joined by stargates
+pub fn index_with_gates(
+    systems: Vec<(SystemId, SolarSystem, String)>,
+    gates: &[(SystemId, SystemId)],
+) -> SpatialIndex {
+    let connections = gates
+        .iter()
+        .enumerate()
+        .map(|(i, &(from, to))| connection(i as u32, from, to, "stargate"))
+        .collect();
+
+    SpatialIndex::from_parts(systems, Vec::new(), Vec::new(), connections)
+}
+
+pub fn connection(id: u32, from: SystemId, to: SystemId, connection_type: &str) -> GateConnection {
+    GateConnection {
+        id,
+        from_system_id: from,
+        to_system_id: to,
+        connection_type: connection_type.to_string(),
+    }
+}
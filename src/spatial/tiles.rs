@@ -0,0 +1,267 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{Point3D, SystemId};
+
+/// Most systems a tile holds; larger tiles are split and keep only a sample
+pub const TILE_CAPACITY: usize = 512;
+
+/// Deepest octree level, so coincident systems cannot split forever
+pub const MAX_LEVEL: u8 = 16;
+
+/// Tile address: level, then x, y, z cell indices within that level
+pub type TileKey = (u8, u32, u32, u32);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tile {
+    /// Every system in the tile for leaves, a representative sample otherwise.
+    /// Any prefix of the list is itself spread across the tile.
+    pub systems: Vec<SystemId>,
+    /// Number of systems inside the tile's volume
+    pub total: usize,
+    pub leaf: bool,
+    /// Bit `i` is set when child octant `i` holds systems; octant bits are x = 1, y = 2, z = 4
+    pub children: u8,
+}
+
+/// Static octree tiling of every system, for level-of-detail map streaming.
+///
+/// Level 0 is a single cube enclosing the galaxy; each level halves the cube
+/// along every axis. Contents depend only on system positions and IDs, so the
+/// same data always yields the same tiles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TileSet {
+    /// Lower corner of the root cube in meters
+    pub origin: Point3D,
+    /// Edge length of the root cube in meters
+    pub size: f64,
+    tiles: FxHashMap<TileKey, Tile>,
+}
+
+impl TileSet {
+    pub fn build(system_positions: &[(Point3D, SystemId)]) -> Self {
+        let start = std::time::Instant::now();
+        if system_positions.is_empty() {
+            return Self::default();
+        }
+
+        let mut lower = [f64::INFINITY; 3];
+        let mut upper = [f64::NEG_INFINITY; 3];
+        for (position, _) in system_positions {
+            for i in 0..3 {
+                lower[i] = lower[i].min(position[i]);
+                upper[i] = upper[i].max(position[i]);
+            }
+        }
+        let extent = (0..3).map(|i| upper[i] - lower[i]).fold(0.0, f64::max);
+        // Pad so systems on the upper faces still fall inside the last cell
+        let size = if extent > 0.0 { extent * (1.0 + 1e-9) } else { 1.0 };
+
+        let mut tile_set = Self {
+            origin: lower,
+            size,
+            tiles: FxHashMap::default(),
+        };
+        tile_set.build_tile((0, 0, 0, 0), system_positions.to_vec());
+
+        info!(
+            "Built {} map tiles in {:.2?}",
+            tile_set.tiles.len(),
+            start.elapsed()
+        );
+        tile_set
+    }
+
+    pub fn get(&self, key: TileKey) -> Option<&Tile> {
+        self.tiles.get(&key)
+    }
+
+    /// Lower and upper corners of a tile in meters
+    pub fn bounds(&self, (level, x, y, z): TileKey) -> (Point3D, Point3D) {
+        let cell = self.size / f64::from(1u32 << level);
+        let lower = [
+            self.origin[0] + f64::from(x) * cell,
+            self.origin[1] + f64::from(y) * cell,
+            self.origin[2] + f64::from(z) * cell,
+        ];
+        (lower, [lower[0] + cell, lower[1] + cell, lower[2] + cell])
+    }
+
+    /// Store the tile and its descendants, returning the tile's system list
+    fn build_tile(&mut self, key: TileKey, mut members: Vec<(Point3D, SystemId)>) -> Vec<SystemId> {
+        let (level, x, y, z) = key;
+        let total = members.len();
+
+        if total <= TILE_CAPACITY || level == MAX_LEVEL {
+            members.sort_unstable_by_key(|&(_, id)| id);
+            let systems = spread_order(&members);
+            self.tiles.insert(
+                key,
+                Tile {
+                    systems: systems.clone(),
+                    total,
+                    leaf: true,
+                    children: 0,
+                },
+            );
+            return systems;
+        }
+
+        // Split at the tile's midpoint along each axis
+        let (lower, upper) = self.bounds(key);
+        let middle: Point3D = std::array::from_fn(|i| (lower[i] + upper[i]) / 2.0);
+        let mut octants: [Vec<(Point3D, SystemId)>; 8] = Default::default();
+        for member in members {
+            let octant = (0..3)
+                .filter(|&i| member.0[i] >= middle[i])
+                .fold(0, |bits, i| bits | (1 << i));
+            octants[octant].push(member);
+        }
+
+        let mut children = 0u8;
+        let mut samples: Vec<(usize, Vec<SystemId>)> = Vec::new();
+        for (octant, members) in octants.into_iter().enumerate() {
+            if members.is_empty() {
+                continue;
+            }
+            children |= 1 << octant;
+            let child = (
+                level + 1,
+                x * 2 + (octant & 1) as u32,
+                y * 2 + ((octant >> 1) & 1) as u32,
+                z * 2 + ((octant >> 2) & 1) as u32,
+            );
+            let child_total = members.len();
+            samples.push((child_total, self.build_tile(child, members)));
+        }
+
+        let systems = proportional_merge(&samples, TILE_CAPACITY);
+        self.tiles.insert(
+            key,
+            Tile {
+                systems: systems.clone(),
+                total,
+                leaf: false,
+                children,
+            },
+        );
+        systems
+    }
+}
+
+/// Farthest-point ordering: start nearest the centroid, then repeatedly take
+/// the system farthest from all those already taken. Ties go to the lower ID.
+fn spread_order(members: &[(Point3D, SystemId)]) -> Vec<SystemId> {
+    if members.is_empty() {
+        return Vec::new();
+    }
+
+    let count = members.len() as f64;
+    let centroid: Point3D =
+        std::array::from_fn(|i| members.iter().map(|(position, _)| position[i]).sum::<f64>() / count);
+    let squared = |a: Point3D, b: Point3D| (0..3).map(|i| (a[i] - b[i]).powi(2)).sum::<f64>();
+
+    let first = (0..members.len())
+        .min_by(|&a, &b| squared(members[a].0, centroid).total_cmp(&squared(members[b].0, centroid)))
+        .unwrap_or(0);
+
+    let mut taken = vec![false; members.len()];
+    let mut gap: Vec<f64> = members.iter().map(|_| f64::INFINITY).collect();
+    let mut order = Vec::with_capacity(members.len());
+    let mut next = Some(first);
+
+    while let Some(current) = next {
+        taken[current] = true;
+        order.push(members[current].1);
+
+        next = None;
+        for i in 0..members.len() {
+            if taken[i] {
+                continue;
+            }
+            gap[i] = gap[i].min(squared(members[i].0, members[current].0));
+            if next.is_none_or(|best: usize| gap[i] > gap[best]) {
+                next = Some(i);
+            }
+        }
+    }
+    order
+}
+
+/// Interleave child samples so every prefix draws from each child in
+/// proportion to the number of systems it holds
+fn proportional_merge(samples: &[(usize, Vec<SystemId>)], capacity: usize) -> Vec<SystemId> {
+    let mut taken = vec![0usize; samples.len()];
+    let mut merged = Vec::with_capacity(capacity);
+
+    while merged.len() < capacity {
+        // The child furthest below its share goes next
+        let next = (0..samples.len())
+            .filter(|&i| taken[i] < samples[i].1.len())
+            .min_by(|&a, &b| {
+                let share = |i: usize| (taken[i] + 1) as f64 / samples[i].0 as f64;
+                share(a).total_cmp(&share(b))
+            });
+        let Some(child) = next else { break };
+        merged.push(samples[child].1[taken[child]]);
+        taken[child] += 1;
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proportional_merge, TileSet, TILE_CAPACITY};
+    use crate::coordinates::Coordinate3D;
+
+    #[test]
+    fn test_tiles_sample_coarse_levels_and_keep_leaves_whole() {
+        // Two dense clusters, far apart: one 3x larger than the other
+        let mut positions = Vec::new();
+        for i in 0..1200u32 {
+            let (base, id) = if i < 900 { (0.0, i) } else { (1000.0, i) };
+            let offset = f64::from(i % 30);
+            let position = Coordinate3D::from_light_years(base + offset, offset * 0.5, f64::from(i / 30))
+                .to_meters_array();
+            positions.push((position, id + 1));
+        }
+
+        let tiles = TileSet::build(&positions);
+        let root = tiles.get((0, 0, 0, 0)).unwrap();
+        assert!(!root.leaf);
+        assert_eq!(root.total, 1200);
+        assert_eq!(root.systems.len(), TILE_CAPACITY);
+
+        // The sample keeps the clusters' 3:1 ratio
+        let large = root.systems.iter().filter(|&&id| id <= 900).count();
+        assert!((large as f64 / TILE_CAPACITY as f64 - 0.75).abs() < 0.01);
+
+        // Every system appears in exactly one leaf
+        let mut leaf_systems: Vec<u32> = tiles
+            .tiles
+            .values()
+            .filter(|tile| tile.leaf)
+            .flat_map(|tile| tile.systems.iter().copied())
+            .collect();
+        leaf_systems.sort_unstable();
+        assert_eq!(leaf_systems, (1..=1200).collect::<Vec<u32>>());
+
+        // Input order does not change the result
+        positions.reverse();
+        let rebuilt = TileSet::build(&positions);
+        assert_eq!(rebuilt.tiles.len(), tiles.tiles.len());
+        for (key, tile) in &tiles.tiles {
+            assert_eq!(rebuilt.get(*key).unwrap().systems, tile.systems);
+        }
+    }
+
+    #[test]
+    fn test_proportional_merge_prefixes() {
+        // Twice as many systems in the first child: two picks for every one
+        let samples = vec![(20, vec![1, 2, 3, 4]), (10, vec![10, 11])];
+        assert_eq!(proportional_merge(&samples, 3), vec![1, 2, 10]);
+        // Short samples run out rather than padding
+        assert_eq!(proportional_merge(&samples, 10), vec![1, 2, 10, 3, 4, 11]);
+    }
+}
@@ -0,0 +1,256 @@
+use super::routing::{RouteOptions, RoutePlan};
+use super::{SpatialIndex, SystemId};
+
+/// A visiting order over a set of stops together with the full route through them
+#[derive(Debug, Clone)]
+pub struct TourPlan {
+    /// Stops in visiting order
+    pub stops: Vec<SystemId>,
+    /// Concatenated route through every stop
+    pub route: RoutePlan,
+}
+
+/// Two consecutive stops in the best order found have no route between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnreachableLeg {
+    pub from: SystemId,
+    pub to: SystemId,
+}
+
+impl SpatialIndex {
+    /// Order `stops` to approximately minimise total route cost.
+    ///
+    /// `start` and `end`, when given, are pinned to the ends of the tour and
+    /// need not appear in `stops`; they may be the same system for a round
+    /// trip. Leg costs come from one `route_costs` search per stop and the
+    /// chosen legs from `find_route`, so every routing mode and constraint
+    /// applies. The order is built by nearest-neighbour and refined
+    /// with 2-opt, so it is near-optimal rather than guaranteed optimal.
+    pub fn plan_tour(
+        &self,
+        stops: &[SystemId],
+        start: Option<SystemId>,
+        end: Option<SystemId>,
+        options: &RouteOptions,
+    ) -> Result<TourPlan, UnreachableLeg> {
+        let mut nodes: Vec<SystemId> = start.into_iter().collect();
+        for &id in stops {
+            if !nodes.contains(&id) && Some(id) != end {
+                nodes.push(id);
+            }
+        }
+        // A round trip keeps the start as a separate final node
+        if let Some(end) = end {
+            nodes.push(end);
+        }
+        let start_index = start.map(|_| 0);
+        let end_index = end.map(|_| nodes.len() - 1);
+
+        // One search from each node costs every leg leaving it; legs are not
+        // assumed symmetric because the origin of a route is exempt from
+        // constraints
+        let costs: Vec<Vec<f64>> = nodes
+            .iter()
+            .map(|&from| {
+                self.route_costs(from, &nodes, options)
+                    .into_iter()
+                    .map(|cost| cost.unwrap_or(f64::INFINITY))
+                    .collect()
+            })
+            .collect();
+
+        let order = order_stops(&costs, start_index, end_index);
+
+        let mut route = RoutePlan {
+            systems: vec![nodes[order[0]]],
+            links: Vec::new(),
+            cost: 0.0,
+        };
+        // Only the legs of the chosen order are expanded into routes
+        for pair in order.windows(2) {
+            let (from, to) = (nodes[pair[0]], nodes[pair[1]]);
+            let leg = self.find_route(from, to, options).ok_or(UnreachableLeg { from, to })?;
+            route.systems.extend_from_slice(&leg.systems[1..]);
+            route.links.extend_from_slice(&leg.links);
+            route.cost += leg.cost;
+        }
+
+        Ok(TourPlan {
+            stops: order.into_iter().map(|i| nodes[i]).collect(),
+            route,
+        })
+    }
+}
+
+/// Nearest-neighbour construction followed by 2-opt over a cost matrix.
+///
+/// Returns a permutation of `0..costs.len()` that starts at `start` and ends
+/// at `end` when those are pinned.
+fn order_stops(costs: &[Vec<f64>], start: Option<usize>, end: Option<usize>) -> Vec<usize> {
+    let n = costs.len();
+    if n <= 1 {
+        return (0..n).collect();
+    }
+
+    // With a free start every node is tried as the seed of the greedy tour
+    let seeds: Vec<usize> = match start {
+        Some(start) => vec![start],
+        None => (0..n).filter(|&i| Some(i) != end).collect(),
+    };
+
+    let mut best: Option<(f64, Vec<usize>)> = None;
+    for seed in seeds {
+        let mut order = nearest_neighbour(costs, seed, end);
+        two_opt(costs, &mut order, start.is_some(), end.is_some());
+        let cost = path_cost(costs, &order);
+        if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+            best = Some((cost, order));
+        }
+    }
+
+    best.map(|(_, order)| order).unwrap_or_default()
+}
+
+fn nearest_neighbour(costs: &[Vec<f64>], seed: usize, end: Option<usize>) -> Vec<usize> {
+    let n = costs.len();
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    visited[seed] = true;
+    order.push(seed);
+    if let Some(end) = end {
+        visited[end] = true;
+    }
+
+    let mut current = seed;
+    while let Some(next) = (0..n)
+        .filter(|&i| !visited[i])
+        .min_by(|&a, &b| costs[current][a].total_cmp(&costs[current][b]))
+    {
+        visited[next] = true;
+        order.push(next);
+        current = next;
+    }
+
+    if let Some(end) = end {
+        if end != seed {
+            order.push(end);
+        }
+    }
+    order
+}
+
+/// Reverse segments of `order` while doing so lowers the total cost.
+///
+/// Pinned ends never move. Costs may be asymmetric, so each candidate is
+/// scored on the whole path rather than on the two swapped edges.
+fn two_opt(costs: &[Vec<f64>], order: &mut [usize], pinned_start: bool, pinned_end: bool) {
+    let n = order.len();
+    let first = usize::from(pinned_start);
+    let last = if pinned_end { n.saturating_sub(2) } else { n.saturating_sub(1) };
+    if last <= first {
+        return;
+    }
+
+    let mut best_cost = path_cost(costs, order);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in first..last {
+            for j in (i + 1)..=last {
+                order[i..=j].reverse();
+                let cost = path_cost(costs, order);
+                if cost < best_cost - 1e-9 {
+                    best_cost = cost;
+                    improved = true;
+                } else {
+                    order[i..=j].reverse();
+                }
+            }
+        }
+    }
+}
+
+fn path_cost(costs: &[Vec<f64>], order: &[usize]) -> f64 {
+    order.windows(2).map(|pair| costs[pair[0]][pair[1]]).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::{index_with_gates, system};
+    use super::super::SpatialIndex;
+    use super::{order_stops, UnreachableLeg};
+    use crate::models::RouteOptimize;
+    use crate::spatial::routing::RouteOptions;
+
+    /// Ten systems on a gated line, 0 ly to 9 ly along x
+    fn line_index() -> SpatialIndex {
+        let systems = (1..=10)
+            .map(|id| system(id, &format!("S{}", id), [(id - 1) as f64, 0.0, 0.0]))
+            .collect();
+        let gates: Vec<_> = (1..10).map(|id| (id, id + 1)).collect();
+        index_with_gates(systems, &gates)
+    }
+
+    #[test]
+    fn test_tour_visits_line_in_order() {
+        let index = line_index();
+        let options = RouteOptions::default();
+
+        let tour = index.plan_tour(&[7, 2, 9, 4], None, None, &options).unwrap();
+        assert!(tour.stops == vec![2, 4, 7, 9] || tour.stops == vec![9, 7, 4, 2]);
+        assert_eq!(tour.route.cost, 7.0);
+        assert_eq!(tour.route.systems.len(), 8);
+    }
+
+    #[test]
+    fn test_tour_respects_fixed_ends() {
+        let index = line_index();
+        let options = RouteOptions {
+            optimize: RouteOptimize::Distance,
+            ..RouteOptions::default()
+        };
+
+        // Starting in the middle forces a backtrack; ending at 1 fixes its direction
+        let tour = index.plan_tour(&[2, 9], Some(5), Some(1), &options).unwrap();
+        assert_eq!(tour.stops, vec![5, 9, 2, 1]);
+        assert_eq!(tour.route.systems.first(), Some(&5));
+        assert_eq!(tour.route.systems.last(), Some(&1));
+        assert!((tour.route.cost - 12.0).abs() < 1e-9);
+
+        // A round trip returns to the start
+        let tour = index.plan_tour(&[3, 8], Some(5), Some(5), &options).unwrap();
+        assert_eq!(tour.stops.first(), Some(&5));
+        assert_eq!(tour.stops.last(), Some(&5));
+        assert!((tour.route.cost - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_two_opt_uncrosses_greedy_order() {
+        // Nearest-neighbour from 0 goes 0-1-2-3 (cost 1 + 1 + 10); 0-2-1-3 costs 2 + 1 + 2
+        let costs = vec![
+            vec![0.0, 1.0, 2.0, 9.0],
+            vec![1.0, 0.0, 1.0, 2.0],
+            vec![2.0, 1.0, 0.0, 10.0],
+            vec![9.0, 2.0, 10.0, 0.0],
+        ];
+        assert_eq!(order_stops(&costs, Some(0), None), vec![0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_tour_reports_unreachable_leg() {
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [1.0, 0.0, 0.0]),
+                system(3, "C", [2.0, 0.0, 0.0]),
+            ],
+            &[(1, 2)],
+        );
+
+        let err = index
+            .plan_tour(&[2, 3], Some(1), None, &RouteOptions::default())
+            .unwrap_err();
+        assert_eq!(err, UnreachableLeg { from: 2, to: 3 });
+    }
+}
@@ -0,0 +1,368 @@
+use rstar::primitives::GeomWithData;
+use rstar::{RTree, AABB};
+
+use super::{Point3D, SpatialIndex, SystemId};
+
+/// R-tree entry: a system center tagged with its ID
+pub type SystemPoint = GeomWithData<Point3D, SystemId>;
+
+/// 3x3 rotation matrix; column `i` is the world direction of local axis `i`
+pub type Rotation = [[f64; 3]; 3];
+
+pub const IDENTITY: Rotation = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// Build an R-tree over system centers for volume queries
+pub fn build_rtree(system_positions: &[(Point3D, SystemId)]) -> RTree<SystemPoint> {
+    RTree::bulk_load(
+        system_positions
+            .iter()
+            .map(|&(position, system_id)| SystemPoint::new(position, system_id))
+            .collect(),
+    )
+}
+
+/// Rotation matrix of a quaternion `(x, y, z, w)`, or `None` if it has no length
+pub fn rotation_from_quaternion(x: f64, y: f64, z: f64, w: f64) -> Option<Rotation> {
+    let norm = (x * x + y * y + z * z + w * w).sqrt();
+    if !norm.is_finite() || norm < f64::EPSILON {
+        return None;
+    }
+    let (x, y, z, w) = (x / norm, y / norm, z / norm, w / norm);
+
+    Some([
+        [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w)],
+        [2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w)],
+        [2.0 * (x * z - y * w), 2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y)],
+    ])
+}
+
+/// A box that may be rotated about its center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBox {
+    pub center: Point3D,
+    /// Half the box size along each local axis
+    pub half_extents: [f64; 3],
+    pub rotation: Rotation,
+}
+
+impl OrientedBox {
+    /// The axis-aligned box spanning two opposite corners
+    pub fn from_corners(a: Point3D, b: Point3D) -> Self {
+        let center = [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0, (a[2] + b[2]) / 2.0];
+        let half_extents = [
+            (a[0] - b[0]).abs() / 2.0,
+            (a[1] - b[1]).abs() / 2.0,
+            (a[2] - b[2]).abs() / 2.0,
+        ];
+        Self {
+            center,
+            half_extents,
+            rotation: IDENTITY,
+        }
+    }
+
+    /// The same box rotated about its center
+    pub fn rotated(self, rotation: Rotation) -> Self {
+        Self { rotation, ..self }
+    }
+
+    pub fn contains(&self, point: Point3D) -> bool {
+        let offset = [
+            point[0] - self.center[0],
+            point[1] - self.center[1],
+            point[2] - self.center[2],
+        ];
+        // Project onto each local axis (a column of the rotation)
+        (0..3).all(|axis| {
+            let along = (0..3).map(|row| self.rotation[row][axis] * offset[row]).sum::<f64>();
+            along.abs() <= self.half_extents[axis]
+        })
+    }
+
+    /// Smallest axis-aligned envelope containing the box
+    pub fn envelope(&self) -> AABB<Point3D> {
+        let reach: [f64; 3] = std::array::from_fn(|row| {
+            (0..3)
+                .map(|axis| self.rotation[row][axis].abs() * self.half_extents[axis])
+                .sum()
+        });
+        AABB::from_corners(
+            std::array::from_fn(|i| self.center[i] - reach[i]),
+            std::array::from_fn(|i| self.center[i] + reach[i]),
+        )
+    }
+}
+
+/// A perspective camera's view volume.
+///
+/// Follows the WebGL convention: in camera space the camera looks down -Z
+/// with +Y up, and `rotation` turns camera space into world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub position: Point3D,
+    pub rotation: Rotation,
+    /// Vertical field of view in radians
+    pub fov_y: f64,
+    /// Width over height of the viewport
+    pub aspect: f64,
+    pub near: f64,
+    pub far: f64,
+}
+
+impl Frustum {
+    /// Camera-space coordinates of a world point
+    fn camera_space(&self, point: Point3D) -> [f64; 3] {
+        let offset = [
+            point[0] - self.position[0],
+            point[1] - self.position[1],
+            point[2] - self.position[2],
+        ];
+        std::array::from_fn(|axis| (0..3).map(|row| self.rotation[row][axis] * offset[row]).sum())
+    }
+
+    fn half_tangents(&self) -> (f64, f64) {
+        let vertical = (self.fov_y / 2.0).tan();
+        (vertical * self.aspect, vertical)
+    }
+
+    pub fn contains(&self, point: Point3D) -> bool {
+        let [x, y, z] = self.camera_space(point);
+        let depth = -z;
+        let (tan_x, tan_y) = self.half_tangents();
+        depth >= self.near && depth <= self.far && x.abs() <= depth * tan_x && y.abs() <= depth * tan_y
+    }
+
+    /// Smallest axis-aligned envelope containing the eight frustum corners
+    pub fn envelope(&self) -> AABB<Point3D> {
+        let (tan_x, tan_y) = self.half_tangents();
+        let mut lower = [f64::INFINITY; 3];
+        let mut upper = [f64::NEG_INFINITY; 3];
+
+        for depth in [self.near, self.far] {
+            for (sx, sy) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+                let local = [sx * depth * tan_x, sy * depth * tan_y, -depth];
+                for i in 0..3 {
+                    let world = self.position[i] + (0..3).map(|axis| self.rotation[i][axis] * local[axis]).sum::<f64>();
+                    lower[i] = lower[i].min(world);
+                    upper[i] = upper[i].max(world);
+                }
+            }
+        }
+
+        AABB::from_corners(lower, upper)
+    }
+}
+
+/// Every point within `radius` of the segment from `start` to `end`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corridor {
+    pub start: Point3D,
+    pub end: Point3D,
+    pub radius: f64,
+}
+
+/// A system inside a corridor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CorridorHit {
+    pub system_id: SystemId,
+    /// Projection onto the segment, measured from `start`; slightly negative or
+    /// past the end for systems in the rounded end caps
+    pub along: f64,
+    /// Distance from the nearest point of the segment
+    pub offset: f64,
+}
+
+impl Corridor {
+    pub fn length(&self) -> f64 {
+        super::routing::euclidean(self.start, self.end)
+    }
+
+    /// Projection along the segment and distance from it, or `None` when the
+    /// point is outside the corridor
+    pub fn locate(&self, point: Point3D) -> Option<(f64, f64)> {
+        let length = self.length();
+        let along = if length > 0.0 {
+            (0..3)
+                .map(|i| (point[i] - self.start[i]) * (self.end[i] - self.start[i]))
+                .sum::<f64>()
+                / length
+        } else {
+            0.0
+        };
+
+        let t = if length > 0.0 { (along / length).clamp(0.0, 1.0) } else { 0.0 };
+        let nearest: Point3D = std::array::from_fn(|i| self.start[i] + t * (self.end[i] - self.start[i]));
+        let offset = super::routing::euclidean(point, nearest);
+
+        (offset <= self.radius).then_some((along, offset))
+    }
+
+    pub fn envelope(&self) -> AABB<Point3D> {
+        AABB::from_corners(
+            std::array::from_fn(|i| self.start[i].min(self.end[i]) - self.radius),
+            std::array::from_fn(|i| self.start[i].max(self.end[i]) + self.radius),
+        )
+    }
+}
+
+impl SpatialIndex {
+    /// Systems within the corridor, ordered by their projection along it
+    pub fn find_systems_in_corridor(&self, corridor: &Corridor) -> Vec<CorridorHit> {
+        let mut found: Vec<CorridorHit> = self
+            .rtree
+            .locate_in_envelope(&corridor.envelope())
+            .filter_map(|point| {
+                corridor
+                    .locate(*point.geom())
+                    .map(|(along, offset)| CorridorHit {
+                        system_id: point.data,
+                        along,
+                        offset,
+                    })
+            })
+            .collect();
+        found.sort_by(|a, b| a.along.total_cmp(&b.along).then_with(|| a.system_id.cmp(&b.system_id)));
+        found
+    }
+
+    /// Systems inside the camera frustum with their distance from the camera in
+    /// meters, nearest first
+    pub fn find_systems_in_frustum(&self, frustum: &Frustum) -> Vec<(SystemId, f64)> {
+        let mut found: Vec<(SystemId, f64)> = self
+            .rtree
+            .locate_in_envelope(&frustum.envelope())
+            .filter(|point| frustum.contains(*point.geom()))
+            .map(|point| (point.data, super::routing::euclidean(frustum.position, *point.geom())))
+            .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        found
+    }
+
+    /// Systems whose centers lie inside `volume`, sorted by ID
+    pub fn find_systems_in_box(&self, volume: &OrientedBox) -> Vec<SystemId> {
+        let mut found: Vec<SystemId> = self
+            .rtree
+            .locate_in_envelope(&volume.envelope())
+            .filter(|point| volume.rotation == IDENTITY || volume.contains(*point.geom()))
+            .map(|point| point.data)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_fixtures::{index_with_gates, system};
+    use super::{rotation_from_quaternion, Corridor, Frustum, OrientedBox, IDENTITY};
+    use crate::coordinates::Coordinate3D;
+
+    fn ly(x: f64, y: f64, z: f64) -> [f64; 3] {
+        Coordinate3D::from_light_years(x, y, z).to_meters_array()
+    }
+
+    #[test]
+    fn test_axis_aligned_box() {
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [0.0, 0.0, 0.0]),
+                system(2, "B", [5.0, 5.0, 5.0]),
+                system(3, "C", [10.0, 0.0, 0.0]),
+                system(4, "D", [-1.0, 0.0, 0.0]),
+            ],
+            &[],
+        );
+
+        // Corners may be given in either order; the boundary is inclusive
+        let volume = OrientedBox::from_corners(ly(10.0, 5.0, 5.0), ly(0.0, 0.0, 0.0));
+        assert_eq!(index.find_systems_in_box(&volume), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rotated_box() {
+        // A thin 10 x 1 x 1 ly box along x, then turned 45 degrees about z
+        let index = index_with_gates(
+            vec![
+                system(1, "A", [3.0, 3.0, 0.0]),
+                system(2, "B", [4.0, 0.0, 0.0]),
+                system(3, "C", [-3.0, -3.0, 0.0]),
+            ],
+            &[],
+        );
+        let volume = OrientedBox::from_corners(ly(-5.0, -0.5, -0.5), ly(5.0, 0.5, 0.5));
+        assert_eq!(index.find_systems_in_box(&volume), vec![2]);
+
+        let half_turn = std::f64::consts::FRAC_PI_8;
+        let rotation = rotation_from_quaternion(0.0, 0.0, half_turn.sin(), half_turn.cos()).unwrap();
+        let rotated = volume.rotated(rotation);
+        assert_eq!(index.find_systems_in_box(&rotated), vec![1, 3]);
+
+        assert!(rotation_from_quaternion(0.0, 0.0, 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_frustum_culling_sorted_by_distance() {
+        let index = index_with_gates(
+            vec![
+                system(1, "Ahead", [0.0, 0.0, -5.0]),
+                system(2, "Behind", [0.0, 0.0, 5.0]),
+                system(3, "Wide", [6.0, 0.0, -5.0]),
+                system(4, "TooClose", [0.0, 0.0, -0.5]),
+                system(5, "Far", [1.0, 1.0, -9.0]),
+                system(6, "BeyondFar", [0.0, 0.0, -11.0]),
+            ],
+            &[],
+        );
+        let mut frustum = Frustum {
+            position: ly(0.0, 0.0, 0.0),
+            rotation: IDENTITY,
+            fov_y: std::f64::consts::FRAC_PI_2,
+            aspect: 1.0,
+            near: ly(1.0, 0.0, 0.0)[0],
+            far: ly(10.0, 0.0, 0.0)[0],
+        };
+
+        let ids: Vec<u32> = index.find_systems_in_frustum(&frustum).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![1, 5]);
+
+        // A wider viewport takes in the system off to the side
+        frustum.aspect = 1.5;
+        let ids: Vec<u32> = index.find_systems_in_frustum(&frustum).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![1, 3, 5]);
+
+        // Turning the camera around about +Y looks at +Z instead
+        frustum.rotation = rotation_from_quaternion(0.0, 1.0, 0.0, 0.0).unwrap();
+        let ids: Vec<u32> = index.find_systems_in_frustum(&frustum).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn test_corridor_ordered_along_segment() {
+        let index = index_with_gates(
+            vec![
+                system(1, "Start", [0.0, 0.0, 0.0]),
+                system(2, "End", [10.0, 0.0, 0.0]),
+                system(3, "Midway", [5.0, 1.5, 0.0]),
+                system(4, "Early", [2.0, 0.0, -1.0]),
+                system(5, "Aside", [5.0, 3.0, 0.0]),
+                system(6, "EndCap", [11.5, 0.0, 0.0]),
+                system(7, "PastCap", [12.5, 0.0, 0.0]),
+                system(8, "Corner", [11.5, 1.5, 0.0]),
+            ],
+            &[],
+        );
+        let corridor = Corridor {
+            start: ly(0.0, 0.0, 0.0),
+            end: ly(10.0, 0.0, 0.0),
+            radius: ly(2.0, 0.0, 0.0)[0],
+        };
+
+        let hits = index.find_systems_in_corridor(&corridor);
+        let ids: Vec<u32> = hits.iter().map(|hit| hit.system_id).collect();
+        assert_eq!(ids, vec![1, 4, 3, 2, 6]);
+
+        let midway = hits.iter().find(|hit| hit.system_id == 3).unwrap();
+        assert!((midway.along / ly(1.0, 0.0, 0.0)[0] - 5.0).abs() < 1e-9);
+        assert!((midway.offset / ly(1.0, 0.0, 0.0)[0] - 1.5).abs() < 1e-9);
+    }
+}